@@ -0,0 +1,157 @@
+use crate::{Bounds, Node, OsmData, Way};
+use crate::parser::Id;
+
+/// Fluent construction of [OsmData].
+///
+/// Elements added with an id of `0` are assigned the next id above the largest one seen so far.
+/// Unless [OsmDataBuilder::with_bounds] is used, the bounds are computed from the added nodes.
+#[derive(Debug, Default, Clone)]
+pub struct OsmDataBuilder {
+	data: OsmData,
+	bounds: Option<Bounds>,
+	computed_bounds: Option<Bounds>,
+	max_node_id: Id,
+	max_way_id: Id,
+}
+
+impl OsmDataBuilder {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn with_version(mut self, version: impl Into<String>) -> Self {
+		self.data.version = version.into();
+		self
+	}
+
+	pub fn with_generator(mut self, generator: impl Into<String>) -> Self {
+		self.data.generator = generator.into();
+		self
+	}
+
+	pub fn with_copyright(mut self, copyright: impl Into<String>) -> Self {
+		self.data.copyright = copyright.into();
+		self
+	}
+
+	pub fn with_attribution(mut self, attribution: impl Into<String>) -> Self {
+		self.data.attribution = attribution.into();
+		self
+	}
+
+	pub fn with_license(mut self, license: impl Into<String>) -> Self {
+		self.data.license = license.into();
+		self
+	}
+
+	/// Uses fixed [Bounds] instead of computing them from the nodes.
+	pub fn with_bounds(mut self, bounds: Bounds) -> Self {
+		self.bounds = Some(bounds);
+		self
+	}
+
+	pub fn add_node(mut self, mut node: Node) -> Self {
+		if node.id == 0 {
+			node.id = self.max_node_id + 1;
+		}
+		self.max_node_id = self.max_node_id.max(node.id);
+
+		self.computed_bounds.get_or_insert(Bounds::EMPTY).extend(&node.pos);
+		self.data.nodes.insert(node.id, node);
+		self
+	}
+
+	pub fn add_way(mut self, mut way: Way) -> Self {
+		if way.id == 0 {
+			way.id = self.max_way_id + 1;
+		}
+		self.max_way_id = self.max_way_id.max(way.id);
+
+		self.data.ways.insert(way.id, way);
+		self
+	}
+
+	pub fn build(mut self) -> OsmData {
+		self.data.bounds = self.bounds
+			.or(self.computed_bounds)
+			.unwrap_or(Bounds::ZERO);
+		self.data
+	}
+}
+
+impl OsmData {
+	pub fn builder() -> OsmDataBuilder {
+		OsmDataBuilder::new()
+	}
+
+	/// Collects nodes and ways into maps keyed by their ids, later duplicates win.
+	/// Bounds are left at [Bounds::ZERO], use [OsmData::calculate_bounds] to compute them.
+	pub fn from_nodes_and_ways(
+		nodes: impl IntoIterator<Item = Node>,
+		ways: impl IntoIterator<Item = Way>,
+	) -> Self {
+		OsmData {
+			nodes: nodes.into_iter().map(|n| (n.id, n)).collect(),
+			ways: ways.into_iter().map(|w| (w.id, w)).collect(),
+			..Default::default()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests_builder {
+	use super::*;
+	#[cfg(not(feature = "minimal"))]
+	use crate::{parse_str, Coordinate};
+
+	#[test]
+	fn auto_ids() {
+		let data = OsmData::builder()
+			.add_node(Node { id: 5, ..Default::default() })
+			.add_node(Node::default())
+			.add_way(Way::default())
+			.build();
+
+		assert_eq!(data.nodes.keys().copied().max(), Some(6));
+		assert_eq!(data.nodes[&6].id, 6);
+		assert_eq!(data.ways[&1].id, 1);
+	}
+
+	#[test]
+	fn from_iterators() {
+		let nodes = (1..=3).map(|id| Node { id, ..Default::default() });
+		let data = OsmData::from_nodes_and_ways(nodes, [Way { id: 7, ..Default::default() }]);
+
+		assert_eq!(data.nodes.len(), 3);
+		assert_eq!(data.nodes[&2].id, 2);
+		assert!(data.ways.contains_key(&7));
+	}
+
+	#[test]
+	#[cfg(not(feature = "minimal"))]
+	fn matches_parsed() {
+		let parsed = parse_str(r#"{
+			"version": "0.6", "generator": "test", "copyright": "c", "attribution": "a", "license": "l",
+			"bounds": {"minlat": 1.0, "minlon": 2.0, "maxlat": 3.0, "maxlon": 4.0},
+			"elements": [
+				{"type": "node", "id": 1, "lat": 1.0, "lon": 2.0, "timestamp": "", "version": 1, "changeset": 1, "user": ""},
+				{"type": "node", "id": 2, "lat": 3.0, "lon": 4.0, "timestamp": "", "version": 1, "changeset": 1, "user": ""},
+				{"type": "way", "id": 1, "timestamp": "", "version": 1, "changeset": 1, "user": "", "nodes": [1, 2]}
+			]
+		}"#).unwrap();
+
+		let node = |lat, lon| Node { version: 1, changeset: 1, ..Node::from_coordinate(Coordinate::new(lat, lon)) };
+		let built = OsmData::builder()
+			.with_version("0.6")
+			.with_generator("test")
+			.with_copyright("c")
+			.with_attribution("a")
+			.with_license("l")
+			.add_node(node(1., 2.))
+			.add_node(node(3., 4.))
+			.add_way(Way { nodes: vec![1, 2], version: 1, changeset: 1, ..Default::default() })
+			.build();
+
+		assert_eq!(built, parsed);
+	}
+}
@@ -1,10 +1,17 @@
-#[cfg(not(feature = "f64"))] use std::f32::consts::{E, FRAC_PI_2, FRAC_PI_4};
-#[cfg(feature = "f64")] use std::f64::consts::{E, FRAC_PI_2, FRAC_PI_4};
+use std::collections::HashSet;
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
 
-use crate::{Coordinate, Node, OsmData};
-use crate::Float;
+use crate::{Coordinate, Id, Node, OsmData};
+use crate::{narrow, wide, Float};
 
-const R: Float = 6378137.;
+/// WGS84 equatorial radius in meters, used by all functions without an explicit radius.
+pub const EARTH_RADIUS: Float = 6378137.;
+/// WGS84 polar radius in meters, rounded to the meter.
+pub const EARTH_RADIUS_POLAR: Float = 6356752.;
+/// IUGG mean earth radius in meters, rounded to the meter.
+pub const EARTH_RADIUS_MEAN: Float = 6371009.;
+
+pub(crate) const R: Float = EARTH_RADIUS;
 
 #[derive(Copy, Clone)]
 pub enum Projection {
@@ -14,35 +21,117 @@ pub enum Projection {
 	Custom(fn(&mut Coordinate)),
 }
 
+impl Projection {
+	/// Local scale factor at an unprojected [Coordinate]: projected distances divided by `k` are ground distances.
+	/// For [Projection::WebMercator] this is the secant of the latitude.
+	/// Returns [None] for [Projection::Custom], whose scale is unknown.
+	pub fn scale_factor(&self, coord: &Coordinate) -> Option<Float> {
+		match self {
+			Projection::WebMercator => WebMercator.scale_factor(coord),
+			Projection::Custom(_) => None,
+		}
+	}
+}
+
+/// Forward and inverse formulas of a projection, used by [Convert].
+/// Implement it to use projections that [Projection] doesn't cover.
+pub trait ProjectionMath {
+	/// Projects an unprojected [Coordinate] in place.
+	fn forward(&self, c: &mut Coordinate);
+	/// Reverts [ProjectionMath::forward].
+	fn inverse(&self, c: &mut Coordinate);
+
+	/// Local scale factor at an unprojected [Coordinate], see [Projection::scale_factor].
+	/// Returns [None] by default, for projections whose scale is unknown.
+	fn scale_factor(&self, _coord: &Coordinate) -> Option<Float> {
+		None
+	}
+}
+
+impl<T: ProjectionMath + ?Sized> ProjectionMath for &T {
+	fn forward(&self, c: &mut Coordinate) {
+		(**self).forward(c);
+	}
+
+	fn inverse(&self, c: &mut Coordinate) {
+		(**self).inverse(c);
+	}
+
+	fn scale_factor(&self, coord: &Coordinate) -> Option<Float> {
+		(**self).scale_factor(coord)
+	}
+}
+
+/// The formulas of [Projection::WebMercator].
+#[derive(Debug, Default, Copy, Clone)]
+pub struct WebMercator;
+
+impl ProjectionMath for WebMercator {
+	fn forward(&self, c: &mut Coordinate) {
+		c.lat = lat2y(c.lat);
+		c.lon = lon2x(c.lon);
+	}
+
+	fn inverse(&self, c: &mut Coordinate) {
+		c.lat = y2lat(c.lat);
+		c.lon = x2lon(c.lon);
+	}
+
+	/// The secant of the latitude.
+	fn scale_factor(&self, coord: &Coordinate) -> Option<Float> {
+		Some(narrow(1. / wide(coord.lat).to_radians().cos()))
+	}
+}
+
+/// [Projection::Custom] applies the same function in both directions.
+impl ProjectionMath for Projection {
+	fn forward(&self, c: &mut Coordinate) {
+		match self {
+			Projection::WebMercator => WebMercator.forward(c),
+			Projection::Custom(f) => f(c),
+		}
+	}
+
+	fn inverse(&self, c: &mut Coordinate) {
+		match self {
+			Projection::WebMercator => WebMercator.inverse(c),
+			Projection::Custom(f) => f(c),
+		}
+	}
+
+	fn scale_factor(&self, coord: &Coordinate) -> Option<Float> {
+		Projection::scale_factor(self, coord)
+	}
+}
 
 pub trait Convert {
 	fn convert_to(&mut self, p: Projection);
 	fn revert_from(&mut self, p: Projection);
 }
 
+/// Like [Convert], but with any [ProjectionMath], e.g. a projection from another crate.
+pub trait ConvertWith {
+	fn convert_with<P: ProjectionMath>(&mut self, p: P);
+	fn revert_with<P: ProjectionMath>(&mut self, p: P);
+}
+
 impl Convert for Coordinate {
 	fn convert_to(&mut self, p: Projection) {
-		match p {
-			Projection::WebMercator => {
-				self.lat = lat2y(self.lat);
-				self.lon = lon2x(self.lon);
-			}
-			Projection::Custom(f) => {
-				f(self);
-			}
-		}
+		p.forward(self);
 	}
 
 	fn revert_from(&mut self, p: Projection) {
-		match p { 
-			Projection::WebMercator => {
-				self.lat = y2lat(self.lat);
-				self.lon = x2lon(self.lon);
-			}
-			Projection::Custom(f) => {
-				f(self);
-			}
-		}
+		p.inverse(self);
+	}
+}
+
+impl ConvertWith for Coordinate {
+	fn convert_with<P: ProjectionMath>(&mut self, p: P) {
+		p.forward(self);
+	}
+
+	fn revert_with<P: ProjectionMath>(&mut self, p: P) {
+		p.inverse(self);
 	}
 }
 
@@ -56,35 +145,151 @@ impl Convert for Node {
 	}
 }
 
+impl ConvertWith for Node {
+	fn convert_with<P: ProjectionMath>(&mut self, p: P) {
+		self.pos.convert_with(p);
+	}
+
+	fn revert_with<P: ProjectionMath>(&mut self, p: P) {
+		self.pos.revert_with(p);
+	}
+}
+
 impl Convert for OsmData {
 	fn convert_to(&mut self, p: Projection) {
+		self.convert_with(p);
+	}
+
+	fn revert_from(&mut self, p: Projection) {
+		self.revert_with(p);
+	}
+}
+
+impl ConvertWith for OsmData {
+	fn convert_with<P: ProjectionMath>(&mut self, p: P) {
 		for node in self.nodes.values_mut() {
-			node.convert_to(p);
+			node.convert_with(&p);
 		}
 	}
 
-	fn revert_from(&mut self, p: Projection) {
+	fn revert_with<P: ProjectionMath>(&mut self, p: P) {
 		for node in self.nodes.values_mut() {
-			node.revert_from(p);
+			node.revert_with(&p);
+		}
+	}
+}
+
+impl OsmData {
+	/// Applies `f` to the position of every node, like [Convert::convert_to] does for a [Projection].
+	/// Unlike [Projection::Custom], `f` may capture state, e.g. an external transformer.
+	pub fn transform(&mut self, mut f: impl FnMut(&mut Coordinate)) {
+		for node in self.nodes.values_mut() {
+			f(&mut node.pos);
+		}
+	}
+
+	/// Shifts all nodes and the [OsmData::bounds] by the given degrees, without wrapping around.
+	pub fn translate(&mut self, d_lat: Float, d_lon: Float) {
+		let shift = |c: &mut Coordinate| {
+			c.lat += d_lat;
+			c.lon += d_lon;
+		};
+		self.transform(shift);
+		shift(&mut self.bounds.min);
+		shift(&mut self.bounds.max);
+	}
+
+	/// Scales the distances in degrees of all nodes and the [OsmData::bounds] to `about` by `factor`.
+	/// Negative factors mirror the data, the bounds stay normalized.
+	pub fn scale(&mut self, factor: Float, about: &Coordinate) {
+		let scale = |c: &mut Coordinate| {
+			c.lat = about.lat + (c.lat - about.lat) * factor;
+			c.lon = about.lon + (c.lon - about.lon) * factor;
+		};
+		self.transform(scale);
+		scale(&mut self.bounds.min);
+		scale(&mut self.bounds.max);
+		self.bounds = self.bounds.normalized();
+	}
+
+	/// Projects only the nodes with the given ids, unknown ids are ignored.
+	/// Keeping track of which nodes in the same [OsmData] are projected is up to the caller.
+	pub fn convert_nodes(&mut self, ids: &HashSet<Id>, p: impl ProjectionMath) {
+		for id in ids {
+			if let Some(node) = self.nodes.get_mut(id) {
+				node.convert_with(&p);
+			}
 		}
 	}
 }
 
 
 pub fn lat2y(lat: Float) -> Float {
-	(lat.to_radians() / 2. + FRAC_PI_4).tan().log(E) * R
+	lat2y_with_radius(lat, R)
 }
 
 pub fn lon2x(lon: Float) -> Float {
-	R * lon.to_radians()
+	lon2x_with_radius(lon, R)
 }
 
 pub fn y2lat(y: Float) -> Float {
-	(2. * (y / R).exp().atan() - FRAC_PI_2).to_degrees()
+	y2lat_with_radius(y, R)
 }
 
 pub fn x2lon(x: Float) -> Float {
-	(x / R).to_degrees()
+	x2lon_with_radius(x, R)
+}
+
+// The projection math is always done in f64, so accuracy doesn't depend on the stored precision.
+
+pub fn lat2y_with_radius(lat: Float, radius: Float) -> Float {
+	narrow(lat2y_f64_with_radius(wide(lat), wide(radius)))
+}
+
+pub fn lon2x_with_radius(lon: Float, radius: Float) -> Float {
+	narrow(lon2x_f64_with_radius(wide(lon), wide(radius)))
+}
+
+pub fn y2lat_with_radius(y: Float, radius: Float) -> Float {
+	narrow(y2lat_f64_with_radius(wide(y), wide(radius)))
+}
+
+pub fn x2lon_with_radius(x: Float, radius: Float) -> Float {
+	narrow(x2lon_f64_with_radius(wide(x), wide(radius)))
+}
+
+// f64 variants, available regardless of the `f64` feature.
+
+pub fn lat2y_f64(lat: f64) -> f64 {
+	lat2y_f64_with_radius(lat, wide(R))
+}
+
+pub fn lon2x_f64(lon: f64) -> f64 {
+	lon2x_f64_with_radius(lon, wide(R))
+}
+
+pub fn y2lat_f64(y: f64) -> f64 {
+	y2lat_f64_with_radius(y, wide(R))
+}
+
+pub fn x2lon_f64(x: f64) -> f64 {
+	x2lon_f64_with_radius(x, wide(R))
+}
+
+pub fn lat2y_f64_with_radius(lat: f64, radius: f64) -> f64 {
+	(lat.to_radians() / 2. + FRAC_PI_4).tan().ln() * radius
+}
+
+pub fn lon2x_f64_with_radius(lon: f64, radius: f64) -> f64 {
+	radius * lon.to_radians()
+}
+
+pub fn y2lat_f64_with_radius(y: f64, radius: f64) -> f64 {
+	(2. * (y / radius).exp().atan() - FRAC_PI_2).to_degrees()
+}
+
+pub fn x2lon_f64_with_radius(x: f64, radius: f64) -> f64 {
+	(x / radius).to_degrees()
 }
 
 
@@ -92,18 +297,135 @@ pub fn x2lon(x: Float) -> Float {
 mod tests_convert {
 	use super::*;
 
+	/// Maximum round-trip error in degrees. Projected f32 values far from the origin
+	/// are only stored with a resolution of about 2 meters, f64 is exact to the nanodegree.
+	#[cfg(not(feature = "f64"))]
+	const ROUND_TRIP_TOLERANCE: Float = 0.00002;
+	#[cfg(feature = "f64")]
+	const ROUND_TRIP_TOLERANCE: Float = 0.000000001;
+
 	#[test]
 	fn projection_webmercator() {
-		let original = Coordinate::new(50., 10.);
+		for original in [
+			Coordinate::new(50., 10.),
+			Coordinate::new(-50., 10.),
+			Coordinate::new(50., -10.),
+			Coordinate::new(-33.86, -151.21),
+		] {
+			let mut projected = original;
+			projected.convert_to(Projection::WebMercator);
+			assert_eq!(projected.lat.signum(), original.lat.signum());
+			assert_eq!(projected.lon.signum(), original.lon.signum());
 
-		let mut projected = original.clone();
-		projected.convert_to(Projection::WebMercator);
+			let mut reverted = projected;
+			reverted.revert_from(Projection::WebMercator);
+			assert!(reverted.approx_eq(&original, ROUND_TRIP_TOLERANCE), "{original:?} -> {reverted:?}");
+		}
+	}
+
+	#[test]
+	fn projection_precision() {
+		// Reference values computed in f64.
+		let y = lat2y(85.);
+		let x = lon2x(179.5);
+		assert!((wide(y) - 19971868.880408563).abs() / 19971868.880408563 < 1e-7);
+		assert!((wide(x) - 19981848.597392607).abs() / 19981848.597392607 < 1e-7);
+
+		assert!((y2lat(y) - 85.).abs() < 1e-5);
+		assert!((x2lon(x) - 179.5).abs() < 1e-5);
+	}
+
+	#[test]
+	fn projection_f64() {
+		assert!((lat2y_f64(85.) - 19971868.880408563).abs() < 1e-6);
+		assert!((lon2x_f64(179.5) - 19981848.597392607).abs() < 1e-6);
+		assert!((y2lat_f64(lat2y_f64(85.)) - 85.).abs() < 1e-12);
+		assert!((x2lon_f64(lon2x_f64(179.5)) - 179.5).abs() < 1e-12);
+	}
+
+	#[test]
+	fn scale_factor() {
+		let (a, b) = (Coordinate::new(60., 10.), Coordinate::new(60., 10.01));
+		let k = Projection::WebMercator.scale_factor(&a).unwrap();
+		assert!((k - 2.).abs() < 1e-5);
+
+		let projected = lon2x(b.lon) - lon2x(a.lon);
+		assert!((projected / k / a.distance_to(&b) - 1.).abs() < 1e-3);
+
+		assert_eq!(Projection::Custom(|_| {}).scale_factor(&a), None);
+	}
+
+	#[test]
+	fn transform() {
+		let mut data = crate::parse_str(crate::parser::tests_parser::SAMPLE).unwrap();
+		let offset = 10.;
+		let mut count = 0;
+
+		data.transform(|c| {
+			c.lat += offset;
+			count += 1;
+		});
+
+		assert_eq!(count, 4);
+		assert!(data.nodes[&1].pos.approx_eq(&Coordinate::new(51.30365, -81.90171), 1e-5));
+	}
+
+	#[test]
+	fn projection_math() {
+		/// Equirectangular projection in meters.
+		struct PlateCarree;
+
+		impl ProjectionMath for PlateCarree {
+			fn forward(&self, c: &mut Coordinate) {
+				(c.lat, c.lon) = (c.lat.to_radians() * R, c.lon.to_radians() * R);
+			}
+
+			fn inverse(&self, c: &mut Coordinate) {
+				(c.lat, c.lon) = ((c.lat / R).to_degrees(), (c.lon / R).to_degrees());
+			}
+		}
+
+		let mut data = crate::parse_str(crate::parser::tests_parser::SAMPLE).unwrap();
+		let original = data.clone();
+
+		data.convert_with(PlateCarree);
+		assert!((data.nodes[&1].pos.lat - narrow(41.30365_f64.to_radians()) * R).abs() < 1.);
+		data.revert_with(PlateCarree);
+		assert!(data.nodes[&1].pos.approx_eq(&original.nodes[&1].pos, 1e-4));
+
+		let mut a = Coordinate::new(50., 10.);
+		let mut b = a;
+		a.convert_to(Projection::WebMercator);
+		b.convert_with(WebMercator);
+		assert_eq!(a, b);
+
+		// Convert stays object safe
+		let mut c = Coordinate::new(50., 10.);
+		(&mut c as &mut dyn Convert).convert_to(Projection::WebMercator);
+		assert_eq!(c, a);
+
+		let pos = Coordinate::new(60., 10.);
+		assert_eq!(PlateCarree.scale_factor(&pos), None);
+		assert_eq!(WebMercator.scale_factor(&pos), Projection::WebMercator.scale_factor(&pos));
+	}
+
+	#[test]
+	fn affine() {
+		let mut data = crate::parse_str(crate::parser::tests_parser::SAMPLE).unwrap();
+		let original = data.clone();
 
-		let mut reverted = projected.clone();
-		reverted.revert_from(Projection::WebMercator);
+		data.translate(1., -2.);
+		assert!(data.nodes[&1].pos.approx_eq(&Coordinate::new(42.30365, -83.90171), 1e-4));
+		let mut expected = original.bounds;
+		expected.min = Coordinate::new(expected.min.lat + 1., expected.min.lon - 2.);
+		expected.max = Coordinate::new(expected.max.lat + 1., expected.max.lon - 2.);
+		assert!(data.bounds.approx_eq(&expected, 1e-4));
 
-		assert!((original.lat.abs() - reverted.lat.abs()) <= 0.00001);
-		assert!((original.lon.abs() - reverted.lon.abs()) <= 0.00001);
+		let about = Coordinate::new(42., -84.);
+		data.scale(-2., &about);
+		assert!(data.nodes[&1].pos.approx_eq(&Coordinate::new(41.3927, -84.19658), 1e-4));
+		assert!(data.bounds.is_valid());
+		assert!(data.nodes.values().all(|n| data.bounds.contains(&n.pos)));
 	}
 
 	#[test]
@@ -0,0 +1,316 @@
+//! Change files (`.osc`) for keeping a local extract up to date,
+//! see https://wiki.openstreetmap.org/wiki/OsmChange.
+
+use serde::Deserialize;
+
+use crate::{MemberType, Node, Nodes, OsmData, OsmError, Relation, Relations, Way, Ways};
+use crate::parser::{parse_elements, Id, IdMap};
+
+//region Change
+/// Complete elements that are created or modified by a change.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ChangeSet {
+	pub nodes: Nodes,
+	pub ways: Ways,
+	pub relations: Relations,
+}
+
+impl ChangeSet {
+	fn from_elements(elements: Vec<serde_json::Value>) -> Result<Self, OsmError> {
+		let (nodes, ways, relations) = parse_elements(elements)?;
+		Ok(Self { nodes, ways, relations })
+	}
+}
+
+/// Reference to a deleted element.
+/// Without a version, the element is deleted regardless of its current version.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+pub struct Deletion {
+	#[serde(rename = "type")]
+	pub member_type: MemberType,
+	pub id: Id,
+	#[serde(default)]
+	pub version: Option<u32>,
+}
+
+/// A parsed change file.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct OscChange {
+	pub create: ChangeSet,
+	pub modify: ChangeSet,
+	pub delete: Vec<Deletion>,
+}
+
+#[derive(Deserialize)]
+struct RawOscChange {
+	#[serde(default)]
+	create: Vec<serde_json::Value>,
+	#[serde(default)]
+	modify: Vec<serde_json::Value>,
+	#[serde(default)]
+	delete: Vec<Deletion>,
+}
+
+/// Parse a change in JSON form: an object with optional `create`, `modify` and `delete` arrays.
+/// Created and modified elements use the same format as [crate::parse_str],
+/// deleted elements only need their `type`, `id` and optionally `version`.
+pub fn parse_osc(json: &str) -> Result<OscChange, OsmError> {
+	let raw = serde_json::from_str::<RawOscChange>(json)?;
+
+	Ok(OscChange {
+		create: ChangeSet::from_elements(raw.create)?,
+		modify: ChangeSet::from_elements(raw.modify)?,
+		delete: raw.delete,
+	})
+}
+//endregion
+
+//region Apply
+/// An element with a version, nodes and ways have none with the `minimal` feature.
+trait Versioned {
+	fn version(&self) -> Option<u32>;
+}
+
+impl Versioned for Node {
+	#[cfg(not(feature = "minimal"))]
+	fn version(&self) -> Option<u32> {
+		Some(self.version)
+	}
+
+	#[cfg(feature = "minimal")]
+	fn version(&self) -> Option<u32> {
+		None
+	}
+}
+
+impl Versioned for Way {
+	#[cfg(not(feature = "minimal"))]
+	fn version(&self) -> Option<u32> {
+		Some(self.version)
+	}
+
+	#[cfg(feature = "minimal")]
+	fn version(&self) -> Option<u32> {
+		None
+	}
+}
+
+impl Versioned for Relation {
+	fn version(&self) -> Option<u32> {
+		Some(self.version)
+	}
+}
+
+/// Inserts every element of `changes` unless the existing element has the same or a newer version.
+/// Elements without a version always replace existing ones.
+fn upsert<T: Clone + Versioned>(map: &mut IdMap<T>, changes: &IdMap<T>) {
+	for (id, element) in changes {
+		match map.get(id) {
+			Some(existing) if existing.version().zip(element.version()).is_some_and(|(e, c)| e >= c) => {}
+			_ => { map.insert(*id, element.clone()); }
+		}
+	}
+}
+
+/// Removes the element unless it's newer than the deletion.
+fn remove<T: Versioned>(map: &mut IdMap<T>, deletion: &Deletion) {
+	let newer = map.get(&deletion.id)
+		.and_then(T::version)
+		.zip(deletion.version)
+		.is_some_and(|(existing, deleted)| existing > deleted);
+
+	if !newer {
+		map.remove(&deletion.id);
+	}
+}
+
+impl OsmData {
+	/// Applies a change: creates and modifies first, then deletes.
+	///
+	/// Created or modified elements only replace existing ones with a lower version.
+	/// Deletions of unknown ids are no-ops, and elements newer than a versioned deletion are kept.
+	/// With the `minimal` feature, nodes and ways have no version, so changes to them always apply.
+	/// Bounds are not updated.
+	pub fn apply(&mut self, change: &OscChange) {
+		for set in [&change.create, &change.modify] {
+			upsert(&mut self.nodes, &set.nodes);
+			upsert(&mut self.ways, &set.ways);
+			upsert(&mut self.relations, &set.relations);
+		}
+
+		for deletion in &change.delete {
+			match deletion.member_type {
+				MemberType::Node => remove(&mut self.nodes, deletion),
+				MemberType::Way => remove(&mut self.ways, deletion),
+				MemberType::Relation => remove(&mut self.relations, deletion),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests_apply {
+	use super::*;
+	use crate::parse_str;
+	use crate::parser::tests_parser::SAMPLE;
+
+	const CHANGE: &str = r#"{
+		"create": [
+			{"type": "node", "id": 5, "lat": 41.3, "lon": -81.9, "timestamp": "", "version": 1, "changeset": 200, "user": "carol"}
+		],
+		"modify": [
+			{"type": "node", "id": 1, "lat": 41.4, "lon": -81.9, "timestamp": "", "version": 2, "changeset": 200, "user": "carol"},
+			{"type": "node", "id": 2, "lat": 41.4, "lon": -81.9, "timestamp": "", "version": 1, "changeset": 200, "user": "carol"}
+		],
+		"delete": [
+			{"type": "way", "id": 11},
+			{"type": "node", "id": 3, "version": 1},
+			{"type": "node", "id": 4, "version": 0},
+			{"type": "node", "id": 99}
+		]
+	}"#;
+
+	#[test]
+	fn apply() {
+		let mut data = parse_str(SAMPLE).unwrap();
+		let change = parse_osc(CHANGE).unwrap();
+		data.apply(&change);
+
+		// created
+		assert!(data.nodes.contains_key(&5));
+		#[cfg(not(feature = "minimal"))]
+		assert_eq!(data.nodes[&5].user, "carol");
+		// modified with newer version
+		assert_eq!(data.nodes[&1].pos.lat, 41.4);
+		#[cfg(not(feature = "minimal"))]
+		assert_eq!(data.nodes[&1].version, 2);
+		// existing version 2 is newer than the modification, without versions it always applies
+		#[cfg(not(feature = "minimal"))]
+		assert_eq!(data.nodes[&2].user, "alice");
+		#[cfg(feature = "minimal")]
+		assert_eq!(data.nodes[&2].pos.lat, 41.4);
+		// deleted, the unversioned, matching and unknown deletions
+		assert!(!data.ways.contains_key(&11));
+		assert!(!data.nodes.contains_key(&3));
+		assert!(!data.nodes.contains_key(&99));
+		// kept, newer than the deletion, without versions it's deleted too
+		assert_eq!(data.nodes.contains_key(&4), cfg!(not(feature = "minimal")));
+	}
+}
+//endregion
+
+//region Diff
+/// Ids that differ between two element maps, each sorted ascending.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IdDiff {
+	/// Only in the other data.
+	pub added: Vec<Id>,
+	/// Only in this data.
+	pub removed: Vec<Id>,
+	/// In both, but changed.
+	pub modified: Vec<Id>,
+}
+
+impl IdDiff {
+	/// Returns `true` if nothing was added, removed or modified.
+	pub fn is_empty(&self) -> bool {
+		self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+	}
+}
+
+/// Differences between two [OsmData], see [OsmData::diff].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct OsmDiff {
+	pub nodes: IdDiff,
+	pub ways: IdDiff,
+}
+
+impl OsmDiff {
+	/// Returns `true` if neither nodes nor ways differ.
+	pub fn is_empty(&self) -> bool {
+		self.nodes.is_empty() && self.ways.is_empty()
+	}
+}
+
+/// Compares the maps, using `modified` for elements present in both.
+fn diff_ids<T>(old: &IdMap<T>, new: &IdMap<T>, modified: impl Fn(&T, &T) -> bool) -> IdDiff {
+	let mut diff = IdDiff {
+		added: new.keys().filter(|id| !old.contains_key(id)).copied().collect(),
+		removed: old.keys().filter(|id| !new.contains_key(id)).copied().collect(),
+		modified: old.iter()
+			.filter(|(id, element)| new.get(id).is_some_and(|other| modified(element, other)))
+			.map(|(id, _)| *id)
+			.collect(),
+	};
+
+	diff.added.sort_unstable();
+	diff.removed.sort_unstable();
+	diff.modified.sort_unstable();
+	diff
+}
+
+/// Returns `true` if `new` has a higher version, or differs if either has no version.
+fn newer<T: PartialEq + Versioned>(old: &T, new: &T) -> bool {
+	match (old.version(), new.version()) {
+		(Some(old), Some(new)) => new > old,
+		_ => old != new,
+	}
+}
+
+impl OsmData {
+	/// Compares nodes and ways with `other`, which is treated as the newer data.
+	/// Elements with the same id but any different field are modified. Relations and metadata are not compared.
+	pub fn diff(&self, other: &OsmData) -> OsmDiff {
+		OsmDiff {
+			nodes: diff_ids(&self.nodes, &other.nodes, |a, b| a != b),
+			ways: diff_ids(&self.ways, &other.ways, |a, b| a != b),
+		}
+	}
+
+	/// Like [OsmData::diff], but elements are only modified if `other` has a higher version,
+	/// so older or unchanged versions with e.g. rounded coordinates are ignored.
+	/// With the `minimal` feature, this is the same as [OsmData::diff].
+	pub fn diff_versions(&self, other: &OsmData) -> OsmDiff {
+		OsmDiff {
+			nodes: diff_ids(&self.nodes, &other.nodes, newer),
+			ways: diff_ids(&self.ways, &other.ways, newer),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests_diff {
+	use crate::parse_str;
+	use crate::parser::tests_parser::SAMPLE;
+
+	#[test]
+	fn diff() {
+		let data = parse_str(SAMPLE).unwrap();
+		assert!(data.diff(&data).is_empty());
+
+		let mut other = data.clone();
+		other.nodes.get_mut(&1).unwrap().pos.lat += 0.001;
+		let diff = data.diff(&other);
+		assert_eq!(diff.nodes.modified, vec![1]);
+		assert!(diff.nodes.added.is_empty() && diff.nodes.removed.is_empty() && diff.ways.is_empty());
+
+		other.nodes.remove(&2);
+		other.ways.insert(12, other.ways[&10].clone());
+		let diff = data.diff(&other);
+		assert_eq!(diff.nodes.removed, vec![2]);
+		assert_eq!(diff.ways.added, vec![12]);
+	}
+
+	#[cfg(not(feature = "minimal"))]
+	#[test]
+	fn diff_versions() {
+		let data = parse_str(SAMPLE).unwrap();
+		let mut other = data.clone();
+		other.nodes.get_mut(&1).unwrap().pos.lat += 0.001;
+		other.nodes.get_mut(&3).unwrap().version += 1;
+
+		assert_eq!(data.diff_versions(&other).nodes.modified, vec![3]);
+		assert_eq!(other.diff_versions(&data).nodes.modified, Vec::<u64>::new());
+	}
+}
+//endregion
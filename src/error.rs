@@ -0,0 +1,129 @@
+use std::fmt;
+
+use crate::MemberType;
+use crate::parser::Id;
+
+/// Errors produced while parsing OSM data.
+#[derive(Debug)]
+pub enum OsmError {
+	/// The input file couldn't be read.
+	Io(std::io::Error),
+	/// The input isn't valid JSON, or doesn't match the expected document layout.
+	Json(serde_json::Error),
+	/// The element at `index` in `elements` couldn't be converted.
+	Element { index: usize, kind: ElementError },
+	/// The PBF input is malformed.
+	#[cfg(feature = "pbf")]
+	Pbf(String),
+	/// The input is valid, but uses a feature this crate doesn't support, e.g. a PBF compression.
+	#[cfg(feature = "pbf")]
+	Unsupported(String),
+	/// The bincode input is malformed or was written by an incompatible version.
+	#[cfg(feature = "bincode")]
+	Bincode(bincode::error::DecodeError),
+}
+
+/// Reasons a single element can fail to convert.
+#[derive(Debug)]
+pub enum ElementError {
+	/// `"type"` is missing or not a string.
+	MissingType,
+	/// `"type"` is not one of the known element types.
+	UnknownType(String),
+	/// The element's fields don't match its type.
+	Invalid(serde_json::Error),
+	/// An element of the same type with this id was already parsed, see [crate::parse_strict].
+	DuplicateId(MemberType, Id),
+}
+
+impl OsmError {
+	/// Returns `true` if the input isn't well-formed, e.g. because a download was truncated.
+	pub fn is_syntax(&self) -> bool {
+		match self {
+			OsmError::Json(e) => e.is_syntax() || e.is_eof(),
+			#[cfg(feature = "pbf")]
+			OsmError::Pbf(_) => true,
+			// only truncation, other decode errors mean the bytes aren't from to_bincode at all
+			#[cfg(feature = "bincode")]
+			OsmError::Bincode(e) => matches!(e, bincode::error::DecodeError::UnexpectedEnd { .. }),
+			_ => false,
+		}
+	}
+
+	/// Returns `true` if the input is well-formed JSON, but its contents couldn't be converted.
+	pub fn is_conversion(&self) -> bool {
+		match self {
+			OsmError::Json(e) => e.is_data(),
+			OsmError::Element { .. } => true,
+			OsmError::Io(_) => false,
+			#[cfg(feature = "pbf")]
+			OsmError::Pbf(_) => false,
+			#[cfg(feature = "pbf")]
+			OsmError::Unsupported(_) => false,
+			#[cfg(feature = "bincode")]
+			OsmError::Bincode(_) => false,
+		}
+	}
+}
+
+impl fmt::Display for OsmError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			OsmError::Io(e) => write!(f, "IO error: {e}"),
+			OsmError::Json(e) => {
+				// serde_json appends the position itself, move it to the front instead.
+				let message = e.to_string();
+				let suffix = format!(" at line {} column {}", e.line(), e.column());
+				let message = message.strip_suffix(&suffix).unwrap_or(&message);
+				write!(f, "JSON error at {}:{}: {message}", e.line(), e.column())
+			}
+			OsmError::Element { index, kind } => write!(f, "element {index}: {kind}"),
+			#[cfg(feature = "pbf")]
+			OsmError::Pbf(message) => write!(f, "PBF error: {message}"),
+			#[cfg(feature = "pbf")]
+			OsmError::Unsupported(message) => write!(f, "unsupported: {message}"),
+			#[cfg(feature = "bincode")]
+			OsmError::Bincode(e) => write!(f, "bincode error: {e}"),
+		}
+	}
+}
+
+impl fmt::Display for ElementError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ElementError::MissingType => write!(f, "\"type\" is not a string"),
+			ElementError::UnknownType(t) => write!(f, "invalid type \"{t}\""),
+			ElementError::Invalid(e) => write!(f, "{e}"),
+			ElementError::DuplicateId(t, id) => write!(f, "duplicate {} id {id}", t.as_str()),
+		}
+	}
+}
+
+impl std::error::Error for OsmError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			OsmError::Io(e) => Some(e),
+			OsmError::Json(e) => Some(e),
+			OsmError::Element { kind: ElementError::Invalid(e), .. } => Some(e),
+			OsmError::Element { .. } => None,
+			#[cfg(feature = "pbf")]
+			OsmError::Pbf(_) | OsmError::Unsupported(_) => None,
+			#[cfg(feature = "bincode")]
+			OsmError::Bincode(e) => Some(e),
+		}
+	}
+}
+
+impl std::error::Error for ElementError {}
+
+impl From<std::io::Error> for OsmError {
+	fn from(value: std::io::Error) -> Self {
+		OsmError::Io(value)
+	}
+}
+
+impl From<serde_json::Error> for OsmError {
+	fn from(value: serde_json::Error) -> Self {
+		OsmError::Json(value)
+	}
+}
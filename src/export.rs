@@ -0,0 +1,625 @@
+use std::io::Write;
+
+use serde_json::{json, Value};
+
+use crate::{wide, Coordinate, Member, OsmData, Tags};
+use crate::convert::{lat2y, lon2x};
+use crate::parser::{Id, IdMap};
+
+/// Returns the values of a map sorted by their id, so exports are reproducible.
+fn sorted<T>(map: &IdMap<T>) -> Vec<&T> {
+	let mut entries = map.iter().collect::<Vec<_>>();
+	entries.sort_unstable_by_key(|(id, _)| **id);
+	entries.into_iter().map(|(_, v)| v).collect()
+}
+
+//region Xml
+/// Escapes a string for use inside a double-quoted XML attribute.
+fn escape(value: &str) -> String {
+	let mut escaped = String::with_capacity(value.len());
+	for c in value.chars() {
+		match c {
+			'&' => escaped.push_str("&amp;"),
+			'<' => escaped.push_str("&lt;"),
+			'>' => escaped.push_str("&gt;"),
+			'"' => escaped.push_str("&quot;"),
+			'\'' => escaped.push_str("&apos;"),
+			'\n' => escaped.push_str("&#10;"),
+			'\r' => escaped.push_str("&#13;"),
+			'\t' => escaped.push_str("&#9;"),
+			c => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+/// Writes the `<tag>` children of an element, sorted by key.
+fn write_tags<W: Write>(w: &mut W, tags: &Tags) -> std::io::Result<()> {
+	let mut tags = tags.iter().collect::<Vec<_>>();
+	tags.sort_unstable();
+	for (k, v) in tags {
+		writeln!(w, "  <tag k=\"{}\" v=\"{}\"/>", escape(k), escape(v))?;
+	}
+	Ok(())
+}
+
+/// Writes the metadata attributes, skipping empty and zero ones of elements that were never uploaded,
+/// since tools like JOSM and osmium reject or misread e.g. `version="0"`.
+fn write_meta<W: Write>(w: &mut W, timestamp: &str, version: u32, changeset: u64, user: &str, uid: u64) -> std::io::Result<()> {
+	if !timestamp.is_empty() {
+		write!(w, " timestamp=\"{}\"", escape(timestamp))?;
+	}
+	if version != 0 {
+		write!(w, " version=\"{version}\"")?;
+	}
+	if changeset != 0 {
+		write!(w, " changeset=\"{changeset}\"")?;
+	}
+	if !user.is_empty() {
+		write!(w, " user=\"{}\"", escape(user))?;
+	}
+	if uid != 0 {
+		write!(w, " uid=\"{uid}\"")?;
+	}
+	Ok(())
+}
+
+/// Writes the `visible` attribute, only for deleted elements.
+fn write_visible<W: Write>(w: &mut W, visible: bool) -> std::io::Result<()> {
+	if !visible {
+		write!(w, " visible=\"false\"")?;
+	}
+	Ok(())
+}
+
+impl OsmData {
+	/// Writes the data as an `.osm` XML document, with elements sorted by id.
+	pub fn to_xml_writer<W: Write>(&self, w: W) -> std::io::Result<()> {
+		self.write_xml(w, None)
+	}
+
+	/// Like [OsmData::to_xml_writer], with coordinates rounded as configured.
+	pub fn to_xml_writer_with<W: Write>(&self, w: W, options: &ToJsonOptions) -> std::io::Result<()> {
+		self.write_xml(w, Some(options))
+	}
+
+	/// Writes coordinates as stored, or rounded if `options` are given.
+	fn write_xml<W: Write>(&self, mut w: W, options: Option<&ToJsonOptions>) -> std::io::Result<()> {
+		let coordinate = |c: &Coordinate| match options {
+			Some(options) => {
+				let (lat, lon) = options.coordinate(c);
+				(lat.to_string(), lon.to_string())
+			}
+			None => (c.lat.to_string(), c.lon.to_string()),
+		};
+
+		writeln!(w, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+		writeln!(
+			w, "<osm version=\"{}\" generator=\"{}\" copyright=\"{}\" attribution=\"{}\" license=\"{}\">",
+			escape(&self.version), escape(&self.generator), escape(&self.copyright), escape(&self.attribution), escape(&self.license),
+		)?;
+
+		let (minlat, minlon) = coordinate(&self.bounds.min);
+		let (maxlat, maxlon) = coordinate(&self.bounds.max);
+		writeln!(w, " <bounds minlat=\"{minlat}\" minlon=\"{minlon}\" maxlat=\"{maxlat}\" maxlon=\"{maxlon}\"/>")?;
+
+		for node in sorted(&self.nodes) {
+			let (lat, lon) = coordinate(&node.pos);
+			write!(w, " <node id=\"{}\" lat=\"{lat}\" lon=\"{lon}\"", node.id)?;
+			#[cfg(not(feature = "minimal"))]
+			write_meta(&mut w, &node.timestamp, node.version, node.changeset, &node.user, node.uid)?;
+			write_visible(&mut w, node.visible)?;
+			match &node.tags {
+				Some(tags) if !tags.is_empty() => {
+					writeln!(w, ">")?;
+					write_tags(&mut w, tags)?;
+					writeln!(w, " </node>")?;
+				}
+				_ => writeln!(w, "/>")?,
+			}
+		}
+
+		for way in sorted(&self.ways) {
+			write!(w, " <way id=\"{}\"", way.id)?;
+			#[cfg(not(feature = "minimal"))]
+			write_meta(&mut w, &way.timestamp, way.version, way.changeset, &way.user, way.uid)?;
+			write_visible(&mut w, way.visible)?;
+			writeln!(w, ">")?;
+			for node in &way.nodes {
+				writeln!(w, "  <nd ref=\"{node}\"/>")?;
+			}
+			if let Some(tags) = &way.tags {
+				write_tags(&mut w, tags)?;
+			}
+			writeln!(w, " </way>")?;
+		}
+
+		for relation in sorted(&self.relations) {
+			write!(w, " <relation id=\"{}\"", relation.id)?;
+			write_meta(&mut w, &relation.timestamp, relation.version, relation.changeset, &relation.user, relation.uid)?;
+			write_visible(&mut w, relation.visible)?;
+			writeln!(w, ">")?;
+			for Member { member_type: t, id, role } in &relation.members {
+				writeln!(w, "  <member type=\"{}\" ref=\"{id}\" role=\"{}\"/>", t.as_str(), escape(role))?;
+			}
+			if let Some(tags) = &relation.tags {
+				write_tags(&mut w, tags)?;
+			}
+			writeln!(w, " </relation>")?;
+		}
+
+		writeln!(w, "</osm>")
+	}
+
+	/// Returns the data as an `.osm` XML document, see [OsmData::to_xml_writer].
+	pub fn to_xml(&self) -> String {
+		let mut buf = Vec::new();
+		self.to_xml_writer(&mut buf).expect("writing to a Vec never fails");
+		String::from_utf8(buf).expect("XML output is valid UTF-8")
+	}
+
+	/// Returns the data as an `.osm` XML document, see [OsmData::to_xml_writer_with].
+	pub fn to_xml_with(&self, options: &ToJsonOptions) -> String {
+		let mut buf = Vec::new();
+		self.to_xml_writer_with(&mut buf, options).expect("writing to a Vec never fails");
+		String::from_utf8(buf).expect("XML output is valid UTF-8")
+	}
+}
+
+#[cfg(test)]
+mod tests_xml {
+	use super::*;
+	use crate::{Node, Nodes, Way, Ways};
+
+	#[test]
+	fn xml() {
+		let tags = Tags::from([("name".to_string(), "Tom & \"Jerry\" <3".to_string())]);
+		let data = OsmData {
+			version: "0.6".to_string(),
+			nodes: Nodes::from_iter([
+				(2, Node { id: 2, tags: Some(tags.clone()), ..Default::default() }),
+				(1, Node {
+					#[cfg(not(feature = "minimal"))]
+					version: 3,
+					#[cfg(not(feature = "minimal"))]
+					user: "me".to_string(),
+					id: 1,
+					visible: false,
+					..Default::default()
+				}),
+			]),
+			ways: Ways::from_iter([(5, Way { id: 5, nodes: vec![1, 2], tags: Some(tags), ..Default::default() })]),
+			..Default::default()
+		};
+
+		// node 2 and the way have no metadata, so none is written
+		#[cfg(not(feature = "minimal"))]
+		let edited = r#" version="3" user="me""#;
+		#[cfg(feature = "minimal")]
+		let edited = "";
+
+		let expected = format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<osm version="0.6" generator="" copyright="" attribution="" license="">
+ <bounds minlat="0" minlon="0" maxlat="0" maxlon="0"/>
+ <node id="1" lat="0" lon="0"{edited} visible="false"/>
+ <node id="2" lat="0" lon="0">
+  <tag k="name" v="Tom &amp; &quot;Jerry&quot; &lt;3"/>
+ </node>
+ <way id="5">
+  <nd ref="1"/>
+  <nd ref="2"/>
+  <tag k="name" v="Tom &amp; &quot;Jerry&quot; &lt;3"/>
+ </way>
+</osm>
+"#);
+		assert_eq!(data.to_xml(), expected);
+	}
+
+	#[test]
+	fn xml_decimals() {
+		let data = OsmData {
+			nodes: Nodes::from_iter([(1, Node::from_coordinate(Coordinate::new(41.30565, -2.5)).with_id(1))]),
+			..Default::default()
+		};
+
+		let xml = data.to_xml_with(&ToJsonOptions { decimals: 2 });
+		assert!(xml.contains(r#"<node id="1" lat="41.31" lon="-2.5""#), "{xml}");
+	}
+}
+//endregion
+
+//region Json
+/// Options for [OsmData::to_json_with] and [OsmData::to_xml_with].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ToJsonOptions {
+	/// Decimal places coordinates are rounded to, OSM itself stores 7.
+	pub decimals: u8,
+}
+
+impl Default for ToJsonOptions {
+	fn default() -> Self {
+		Self { decimals: 7 }
+	}
+}
+
+impl ToJsonOptions {
+	/// Rounds half away from zero, which is symmetric for negative coordinates.
+	pub(crate) fn round(&self, value: crate::Float) -> f64 {
+		let factor = 10f64.powi(self.decimals as i32);
+		(wide(value) * factor).round() / factor
+	}
+
+	pub(crate) fn coordinate(&self, c: &Coordinate) -> (f64, f64) {
+		(self.round(c.lat), self.round(c.lon))
+	}
+}
+
+/// Adds the metadata fields.
+fn insert_meta(element: &mut Value, timestamp: &str, version: u32, changeset: u64, user: &str, uid: u64) {
+	element["timestamp"] = json!(timestamp);
+	element["version"] = json!(version);
+	element["changeset"] = json!(changeset);
+	element["user"] = json!(user);
+	element["uid"] = json!(uid);
+}
+
+/// Adds the optional fields, `visible` only for deleted elements.
+fn insert_optional(element: &mut Value, visible: bool, tags: &Option<Tags>) {
+	if !visible {
+		element["visible"] = json!(false);
+	}
+	if let Some(tags) = tags {
+		element["tags"] = json!(tags);
+	}
+}
+
+/// Adds unmodeled fields that don't collide with the exported ones.
+#[cfg(feature = "preserve-unknown")]
+fn insert_extra(element: &mut Value, extra: &crate::Extra) {
+	if let Value::Object(map) = element {
+		for (k, v) in extra {
+			map.entry(k.clone()).or_insert_with(|| v.clone());
+		}
+	}
+}
+
+impl OsmData {
+	/// Returns the data in the JSON format of the OSM API, which [crate::parse_str] reads.
+	pub fn to_json(&self) -> String {
+		self.to_json_with(&ToJsonOptions::default())
+	}
+
+	/// Like [OsmData::to_json], with elements sorted by id and coordinates rounded as configured.
+	pub fn to_json_with(&self, options: &ToJsonOptions) -> String {
+		let (minlat, minlon) = options.coordinate(&self.bounds.min);
+		let (maxlat, maxlon) = options.coordinate(&self.bounds.max);
+
+		let elements = self.json_elements(options).collect::<Vec<_>>();
+
+		let data = json!({
+			"version": self.version,
+			"generator": self.generator,
+			"copyright": self.copyright,
+			"attribution": self.attribution,
+			"license": self.license,
+			"bounds": {"minlat": minlat, "minlon": minlon, "maxlat": maxlat, "maxlon": maxlon},
+			"elements": elements,
+		});
+		#[cfg(feature = "preserve-unknown")]
+		let data = {
+			let mut data = data;
+			insert_extra(&mut data, &self.extra);
+			data
+		};
+
+		data.to_string()
+	}
+
+	/// Writes every element as JSON on its own line (NDJSON), in the order and format of [OsmData::to_json].
+	/// The document header and bounds are omitted.
+	pub fn to_ndjson_writer<W: Write>(&self, w: W) -> std::io::Result<()> {
+		self.to_ndjson_writer_with(w, &ToJsonOptions::default())
+	}
+
+	/// Like [OsmData::to_ndjson_writer], with coordinates rounded as configured.
+	pub fn to_ndjson_writer_with<W: Write>(&self, mut w: W, options: &ToJsonOptions) -> std::io::Result<()> {
+		for element in self.json_elements(options) {
+			serde_json::to_writer(&mut w, &element)?;
+			w.write_all(b"\n")?;
+		}
+		Ok(())
+	}
+
+	/// All elements as JSON values, nodes then ways then relations, each sorted by id.
+	pub(crate) fn json_elements<'a>(&'a self, options: &'a ToJsonOptions) -> impl Iterator<Item = Value> + 'a {
+		let nodes = sorted(&self.nodes).into_iter().map(|n| {
+			let (lat, lon) = options.coordinate(&n.pos);
+			let mut element = json!({
+				"type": "node",
+				"id": n.id,
+				"lat": lat,
+				"lon": lon,
+			});
+			#[cfg(not(feature = "minimal"))]
+			insert_meta(&mut element, &n.timestamp, n.version, n.changeset, &n.user, n.uid);
+			insert_optional(&mut element, n.visible, &n.tags);
+			#[cfg(feature = "preserve-unknown")]
+			insert_extra(&mut element, &n.extra);
+			element
+		});
+
+		let ways = sorted(&self.ways).into_iter().map(|w| {
+			let mut element = json!({
+				"type": "way",
+				"id": w.id,
+				"nodes": w.nodes,
+			});
+			#[cfg(not(feature = "minimal"))]
+			insert_meta(&mut element, &w.timestamp, w.version, w.changeset, &w.user, w.uid);
+			insert_optional(&mut element, w.visible, &w.tags);
+			#[cfg(feature = "preserve-unknown")]
+			insert_extra(&mut element, &w.extra);
+			element
+		});
+
+		let relations = sorted(&self.relations).into_iter().map(|r| {
+			let mut element = json!({
+				"type": "relation",
+				"id": r.id,
+				"members": r.members,
+			});
+			insert_meta(&mut element, &r.timestamp, r.version, r.changeset, &r.user, r.uid);
+			insert_optional(&mut element, r.visible, &r.tags);
+			#[cfg(feature = "preserve-unknown")]
+			insert_extra(&mut element, &r.extra);
+			element
+		});
+
+		nodes.chain(ways).chain(relations)
+	}
+}
+
+#[cfg(test)]
+mod tests_json {
+	use super::*;
+	use crate::parse_str;
+	use crate::parser::tests_parser::SAMPLE;
+
+	#[test]
+	fn round_trip() {
+		let mut data = parse_str(SAMPLE).unwrap();
+		data.ways.get_mut(&11).unwrap().visible = false;
+		let json = data.to_json();
+
+		assert_eq!(parse_str(&json).unwrap(), data);
+		assert_eq!(parse_str(&json).unwrap().to_json(), json);
+	}
+
+	#[test]
+	fn decimals() {
+		let options = ToJsonOptions { decimals: 2 };
+
+		assert_eq!(options.round(41.30565), 41.31);
+		assert_eq!(options.round(-41.30565), -41.31);
+		assert_eq!(options.round(-0.004), 0.);
+
+		let json = parse_str(SAMPLE).unwrap().to_json_with(&options);
+		assert!(json.contains(r#""lat":41.3,"lon":-81.9"#));
+	}
+
+	#[test]
+	fn ndjson() {
+		let data = parse_str(SAMPLE).unwrap();
+		let mut buf = Vec::new();
+		data.to_ndjson_writer_with(&mut buf, &ToJsonOptions { decimals: 2 }).unwrap();
+
+		let lines = String::from_utf8(buf).unwrap();
+		let elements = lines.lines().map(|l| serde_json::from_str::<Value>(l).unwrap()).collect::<Vec<_>>();
+		assert_eq!(elements.len(), 7);
+		assert_eq!(elements[0]["type"], "node");
+		assert_eq!(elements[0]["lat"], 41.3);
+		assert_eq!(elements[6]["type"], "relation");
+		assert_eq!(crate::parser::parse_elements(elements).unwrap().1.len(), 2);
+	}
+
+	#[cfg(feature = "preserve-unknown")]
+	#[test]
+	fn preserve_unknown() {
+		let json = SAMPLE
+			.replacen(r#""version": "0.6","#, r#""version": "0.6", "osm3s": {"timestamp_osm_base": "2023-03-01"},"#, 1)
+			.replacen(r#""id": 1,"#, r#""id": 1, "source": "survey", "version_note": 7,"#, 1)
+			.replacen(r#""id": 10,"#, r#""id": 10, "center": {"lat": 41.3, "lon": -81.9},"#, 1);
+		let data = parse_str(&json).unwrap();
+
+		assert_eq!(data.extra["osm3s"]["timestamp_osm_base"], "2023-03-01");
+		assert_eq!(data.nodes[&1].extra.keys().collect::<Vec<_>>(), ["source", "version_note"]);
+		assert_eq!(data.ways[&10].extra["center"]["lat"], 41.3);
+		assert!(data.relations[&20].extra.is_empty());
+
+		assert_eq!(parse_str(&data.to_json()).unwrap(), data);
+	}
+}
+//endregion
+
+//region Svg
+impl OsmData {
+	/// Draws the data as an SVG for debugging, projected to Web Mercator and scaled to fit `width` by `height` pixels
+	/// using [OsmData::bounds], keeping the aspect ratio.
+	///
+	/// Closed ways are drawn as polygons, open ways as polylines and tagged nodes as small circles.
+	/// Ways referencing missing nodes are skipped.
+	pub fn to_svg(&self, width: u32, height: u32) -> String {
+		let project = |c: &Coordinate| (wide(lon2x(c.lon)), wide(lat2y(c.lat)));
+		let (min_x, min_y) = project(&self.bounds.min);
+		let (max_x, max_y) = project(&self.bounds.max);
+
+		let scale = (width as f64 / (max_x - min_x)).min(height as f64 / (max_y - min_y));
+		let scale = if scale.is_finite() { scale } else { 1. };
+		let point = |c: &Coordinate| {
+			let (x, y) = project(c);
+			((x - min_x) * scale, (max_y - y) * scale)
+		};
+
+		let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n");
+
+		for way in sorted(&self.ways) {
+			let Some(mut coordinates) = self.way_coordinates(way.id) else { continue };
+			let element = if way.is_closed() {
+				coordinates.pop();
+				"polygon"
+			} else {
+				"polyline"
+			};
+
+			let points = coordinates.iter()
+				.map(|c| {
+					let (x, y) = point(c);
+					format!("{x:.2},{y:.2}")
+				})
+				.collect::<Vec<_>>()
+				.join(" ");
+			svg += &format!(" <{element} points=\"{points}\" fill=\"none\" stroke=\"black\"/>\n");
+		}
+
+		for node in sorted(&self.nodes) {
+			if node.tags.as_ref().is_some_and(|t| !t.is_empty()) {
+				let (x, y) = point(&node.pos);
+				svg += &format!(" <circle cx=\"{x:.2}\" cy=\"{y:.2}\" r=\"2\" fill=\"red\"/>\n");
+			}
+		}
+
+		svg + "</svg>\n"
+	}
+}
+
+#[cfg(test)]
+mod tests_svg {
+	use super::*;
+	use crate::{Bounds, Node, Nodes, Way, Ways};
+
+	#[test]
+	fn svg() {
+		let node = |lat, lon| Node::from_coordinate(Coordinate::new(lat, lon));
+		let data = OsmData {
+			bounds: Bounds::new(Coordinate::new(0., 0.), Coordinate::new(1., 1.)),
+			nodes: Nodes::from_iter([
+				(1, node(0., 0.)),
+				(2, node(0., 1.)),
+				(3, node(1., 1.)),
+				(4, Node { tags: Some(Tags::from([("amenity".to_string(), "bench".to_string())])), ..node(1., 0.) }),
+			]),
+			ways: Ways::from_iter([
+				(1, Way { id: 1, nodes: vec![1, 2, 3, 1], ..Default::default() }),
+				(2, Way { id: 2, nodes: vec![3, 4], ..Default::default() }),
+				(3, Way { id: 3, nodes: vec![3, 99], ..Default::default() }),
+			]),
+			..Default::default()
+		};
+
+		let svg = data.to_svg(100, 100);
+		let lines = svg.lines().collect::<Vec<_>>();
+
+		assert_eq!(lines.len(), 5);
+		assert!(lines[1].starts_with(r#" <polygon points="0.00,100.00 99.99,100.00 99.99,0.00""#));
+		assert!(lines[2].starts_with(r#" <polyline points="99.99,0.00 0.00,0.00""#));
+		assert_eq!(lines[3], r#" <circle cx="0.00" cy="0.00" r="2" fill="red"/>"#);
+	}
+}
+//endregion
+
+//region Wkt
+impl OsmData {
+	/// Returns a [Way](crate::Way) as Well-Known Text in lon-lat order, e.g. for PostGIS.
+	/// Closed ways with at least 4 nodes become a `POLYGON`, ways with a single node a `POINT`,
+	/// since a `LINESTRING` needs at least 2 points, and all others a `LINESTRING`.
+	///
+	/// Returns [None] if the way doesn't exist or references a missing node.
+	pub fn way_wkt(&self, way_id: Id) -> Option<String> {
+		let way = self.ways.get(&way_id)?;
+		let coordinates = self.way_coordinates(way_id)?;
+		if coordinates.is_empty() {
+			return Some("LINESTRING EMPTY".to_string());
+		}
+
+		let points = coordinates.iter()
+			.map(|c| format!("{} {}", c.lon, c.lat))
+			.collect::<Vec<_>>()
+			.join(", ");
+
+		Some(if way.is_closed() && coordinates.len() >= 4 {
+			format!("POLYGON(({points}))")
+		} else if coordinates.len() == 1 {
+			format!("POINT({points})")
+		} else {
+			format!("LINESTRING({points})")
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests_wkt {
+	use crate::{Coordinate, Node, Nodes, OsmData, Way, Ways};
+
+	#[test]
+	fn wkt() {
+		let node = |lat, lon| Node::from_coordinate(Coordinate::new(lat, lon));
+		let data = OsmData {
+			nodes: Nodes::from_iter([(1, node(0., 0.)), (2, node(0., 1.5)), (3, node(-1., 1.5))]),
+			ways: Ways::from_iter([
+				(1, Way { id: 1, nodes: vec![1, 2, 3, 1], ..Default::default() }),
+				(2, Way { id: 2, nodes: vec![2, 3], ..Default::default() }),
+				(3, Way { id: 3, nodes: vec![1, 2, 1], ..Default::default() }),
+				(4, Way { id: 4, ..Default::default() }),
+				(5, Way { id: 5, nodes: vec![1, 99], ..Default::default() }),
+				(7, Way { id: 7, nodes: vec![2], ..Default::default() }),
+			]),
+			..Default::default()
+		};
+
+		assert_eq!(data.way_wkt(1).unwrap(), "POLYGON((0 0, 1.5 0, 1.5 -1, 0 0))");
+		assert_eq!(data.way_wkt(2).unwrap(), "LINESTRING(1.5 0, 1.5 -1)");
+		assert_eq!(data.way_wkt(3).unwrap(), "LINESTRING(0 0, 1.5 0, 0 0)");
+		assert_eq!(data.way_wkt(4).unwrap(), "LINESTRING EMPTY");
+		assert_eq!(data.way_wkt(5), None);
+		assert_eq!(data.way_wkt(6), None);
+		assert_eq!(data.way_wkt(7).unwrap(), "POINT(1.5 0)");
+	}
+}
+//endregion
+
+//region Bincode
+#[cfg(feature = "bincode")]
+impl OsmData {
+	/// Encodes the data with bincode, for caching parsed data between runs.
+	/// The encoding is only meant to be read back by [OsmData::from_bincode] of the same crate version.
+	pub fn to_bincode(&self) -> Vec<u8> {
+		bincode::serde::encode_to_vec(self, bincode::config::standard())
+			.expect("OsmData is always serializable")
+	}
+
+	/// Decodes data written by [OsmData::to_bincode].
+	pub fn from_bincode(bytes: &[u8]) -> Result<OsmData, crate::OsmError> {
+		bincode::serde::decode_from_slice(bytes, bincode::config::standard())
+			.map(|(data, _)| data)
+			.map_err(crate::OsmError::Bincode)
+	}
+}
+
+#[cfg(all(test, feature = "bincode"))]
+mod tests_bincode {
+	use super::*;
+	use crate::parse_str;
+	use crate::parser::tests_parser::SAMPLE;
+
+	#[test]
+	fn round_trip() {
+		let data = parse_str(SAMPLE).unwrap();
+		let bytes = data.to_bincode();
+
+		assert_eq!(OsmData::from_bincode(&bytes).unwrap(), data);
+
+		let err = OsmData::from_bincode(&bytes[..bytes.len() / 2]).unwrap_err();
+		assert!(err.is_syntax());
+	}
+}
+//endregion
@@ -0,0 +1,368 @@
+use std::collections::HashSet;
+
+use crate::{Bounds, Coordinate, MemberType, Node, Nodes, OsmData, Relations, Way, Ways};
+use crate::geometry::point_in_polygon;
+use crate::parser::Id;
+
+//region Subset
+impl OsmData {
+	/// Returns a new [OsmData] with exactly the given nodes and ways.
+	///
+	/// Node refs trump the explicit node set: every node referenced by a selected way is included,
+	/// even if it isn't in `node_ids`, so selected ways always keep their full node list.
+	/// Ids that don't exist are ignored. Metadata and bounds are copied unchanged, relations are dropped.
+	pub fn subset(&self, node_ids: &HashSet<Id>, way_ids: &HashSet<Id>) -> OsmData {
+		self.subset_by(node_ids.iter().copied(), way_ids.iter().copied())
+	}
+
+	/// See [OsmData::subset].
+	pub(crate) fn subset_by(&self, node_ids: impl Iterator<Item = Id>, way_ids: impl Iterator<Item = Id>) -> OsmData {
+		let ways = way_ids
+			.filter_map(|id| self.ways.get_key_value(&id))
+			.map(|(id, way)| (*id, way.clone()))
+			.collect::<Ways>();
+
+		let nodes = node_ids
+			.chain(ways.values().flat_map(|w| w.nodes.iter().copied()))
+			.filter_map(|id| self.nodes.get_key_value(&id))
+			.map(|(id, node)| (*id, node.clone()))
+			.collect::<Nodes>();
+
+		OsmData {
+			version: self.version.clone(),
+			generator: self.generator.clone(),
+			copyright: self.copyright.clone(),
+			attribution: self.attribution.clone(),
+			license: self.license.clone(),
+			bounds: self.bounds,
+			nodes,
+			ways,
+			relations: Relations::default(),
+
+			#[cfg(feature = "preserve-unknown")]
+			extra: self.extra.clone(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests_subset {
+	use super::*;
+	use crate::parser::tests_parser::SAMPLE;
+	use crate::parse_str;
+
+	#[test]
+	fn subset() {
+		let data = parse_str(SAMPLE).unwrap();
+		let subset = data.subset(&HashSet::from([4, 99]), &HashSet::from([10]));
+
+		let mut nodes = subset.nodes.keys().copied().collect::<Vec<_>>();
+		nodes.sort_unstable();
+
+		assert_eq!(nodes, vec![1, 2, 4]);
+		assert_eq!(subset.ways.keys().collect::<Vec<_>>(), vec![&10]);
+		assert_eq!(subset.nodes[&2], data.nodes[&2]);
+	}
+}
+//endregion
+
+//region Retain
+impl OsmData {
+	/// Keeps only the nodes for which `f` returns `true`, like [std::collections::HashMap::retain].
+	///
+	/// Ways are not touched and may reference removed nodes afterwards,
+	/// use [OsmData::retain_ways_keep_nodes] to keep the nodes of retained ways.
+	pub fn retain_nodes(&mut self, f: impl Fn(&Node) -> bool) {
+		self.nodes.retain(|_, n| f(n));
+	}
+
+	/// Keeps only the ways for which `f` returns `true`, like [std::collections::HashMap::retain].
+	///
+	/// Nodes of removed ways are kept, use [OsmData::prune_orphan_nodes] to remove them.
+	pub fn retain_ways(&mut self, f: impl Fn(&Way) -> bool) {
+		self.ways.retain(|_, w| f(w));
+	}
+
+	/// Keeps only the ways for which `ways` returns `true` and the nodes for which `nodes` returns `true`.
+	///
+	/// Like in [OsmData::subset], node refs trump the node predicate:
+	/// nodes of retained ways are always kept, so those keep their full node list.
+	pub fn retain_ways_keep_nodes(&mut self, ways: impl Fn(&Way) -> bool, nodes: impl Fn(&Node) -> bool) {
+		self.retain_ways(ways);
+
+		let referenced = self.ways.values().flat_map(|w| &w.nodes).copied().collect::<HashSet<_>>();
+		self.nodes.retain(|id, n| referenced.contains(id) || nodes(n));
+	}
+
+	/// Removes all elements that are marked as deleted, see [Node::visible].
+	/// Ways and relations referencing them are kept.
+	pub fn drop_invisible(&mut self) {
+		self.nodes.retain(|_, n| n.visible);
+		self.ways.retain(|_, w| w.visible);
+		self.relations.retain(|_, r| r.visible);
+	}
+
+	/// Removes untagged nodes that aren't referenced by any way or relation.
+	/// Returns the number of removed nodes.
+	pub fn prune_orphan_nodes(&mut self) -> usize {
+		let referenced = self.ways.values()
+			.flat_map(|w| w.nodes.iter().copied())
+			.chain(self.relations.values()
+				.flat_map(|r| &r.members)
+				.filter(|m| m.member_type == MemberType::Node)
+				.map(|m| m.id))
+			.collect::<HashSet<_>>();
+
+		let before = self.nodes.len();
+		self.nodes.retain(|id, n| referenced.contains(id) || n.tags.as_ref().is_some_and(|t| !t.is_empty()));
+		before - self.nodes.len()
+	}
+}
+
+#[cfg(test)]
+mod tests_retain {
+	use super::*;
+	use crate::parser::tests_parser::SAMPLE;
+	use crate::parse_str;
+
+	fn sorted_ids<T>(map: &crate::IdMap<T>) -> Vec<Id> {
+		let mut ids = map.keys().copied().collect::<Vec<_>>();
+		ids.sort_unstable();
+		ids
+	}
+
+	#[test]
+	#[cfg(not(feature = "minimal"))]
+	fn retain() {
+		let mut data = parse_str(SAMPLE).unwrap();
+		data.retain_nodes(|n| n.user == "alice");
+		assert_eq!(sorted_ids(&data.nodes), vec![1, 2]);
+		assert_eq!(data.ways.len(), 2);
+
+		let mut data = parse_str(SAMPLE).unwrap();
+		data.retain_ways(|w| w.id == 10);
+		assert_eq!(sorted_ids(&data.ways), vec![10]);
+		assert_eq!(data.prune_orphan_nodes(), 2);
+		assert_eq!(sorted_ids(&data.nodes), vec![1, 2]);
+	}
+
+	#[test]
+	fn invisible() {
+		let json = SAMPLE
+			.replace(r#""id": 4, "#, r#""id": 4, "visible": false, "#)
+			.replace(r#""id": 11, "#, r#""id": 11, "visible": false, "#);
+		let mut data = parse_str(&json).unwrap();
+		assert!(data.nodes[&3].visible && !data.nodes[&4].visible);
+
+		data.drop_invisible();
+		assert_eq!(sorted_ids(&data.nodes), vec![1, 2, 3]);
+		assert_eq!(sorted_ids(&data.ways), vec![10]);
+		assert_eq!(data.relations.len(), 1);
+	}
+
+	#[test]
+	fn keep_nodes() {
+		let mut data = parse_str(SAMPLE).unwrap();
+		data.retain_ways_keep_nodes(|w| w.id == 11, |n| n.id == 1);
+
+		assert_eq!(sorted_ids(&data.ways), vec![11]);
+		assert_eq!(sorted_ids(&data.nodes), vec![1, 3, 4]);
+	}
+}
+//endregion
+
+//region Clip
+impl OsmData {
+	/// Removes everything outside of `bounds`.
+	///
+	/// Ways with at least one node inside are kept whole, including their nodes outside of `bounds`.
+	/// Relations are kept if any of their node or way members, or any of their kept member relations, is kept,
+	/// so super-relations survive with their children. Member lists are unchanged.
+	/// The `bounds` field is not updated.
+	pub fn clip_to_bounds(&mut self, bounds: &Bounds) {
+		let inside = self.nodes.values()
+			.filter(|n| bounds.contains(&n.pos))
+			.map(|n| n.id)
+			.collect::<HashSet<_>>();
+
+		self.ways.retain(|_, w| w.nodes.iter().any(|n| inside.contains(n)));
+
+		let referenced = self.ways.values().flat_map(|w| &w.nodes).copied().collect::<HashSet<_>>();
+		self.nodes.retain(|id, _| inside.contains(id) || referenced.contains(id));
+
+		let (nodes, ways) = (&self.nodes, &self.ways);
+		let mut kept = self.relations.values()
+			.filter(|r| r.members.iter().any(|m| match m.member_type {
+				MemberType::Node => nodes.contains_key(&m.id),
+				MemberType::Way => ways.contains_key(&m.id),
+				MemberType::Relation => false,
+			}))
+			.map(|r| r.id)
+			.collect::<HashSet<_>>();
+
+		// add the parents of kept relations until no more are found, nesting can be arbitrarily deep
+		loop {
+			let parents = self.relations.values()
+				.filter(|r| !kept.contains(&r.id))
+				.filter(|r| r.members.iter().any(|m| m.member_type == MemberType::Relation && kept.contains(&m.id)))
+				.map(|r| r.id)
+				.collect::<Vec<_>>();
+			if parents.is_empty() {
+				break;
+			}
+			kept.extend(parents);
+		}
+
+		self.relations.retain(|id, _| kept.contains(id));
+	}
+}
+
+impl OsmData {
+	/// Returns a new [OsmData] with the nodes inside `polygon` and the ways with at least one node inside,
+	/// which are kept whole like in [OsmData::subset]. Relations are dropped.
+	///
+	/// The polygon may be closed explicitly or implicitly, its winding doesn't matter.
+	/// It uses the even-odd rule, so self-intersecting parts are alternately inside and outside.
+	pub fn clip_to_polygon(&self, polygon: &[Coordinate]) -> OsmData {
+		let inside = self.nodes.values()
+			.filter(|n| point_in_polygon(&n.pos, polygon))
+			.map(|n| n.id)
+			.collect::<HashSet<_>>();
+
+		let ways = self.ways.values()
+			.filter(|w| w.nodes.iter().any(|n| inside.contains(n)))
+			.map(|w| w.id);
+
+		self.subset_by(inside.iter().copied(), ways)
+	}
+}
+
+#[cfg(test)]
+mod tests_clip {
+	use super::*;
+	use crate::{parse_clipped, parse_str, Member, Relation};
+	use crate::parser::tests_parser::SAMPLE;
+
+	#[test]
+	fn clip() {
+		// only contains node 1 of way 10
+		let bounds = Bounds::new(Coordinate::new(41.3036, -81.9018), Coordinate::new(41.3037, -81.9017));
+		let data = parse_clipped(SAMPLE, &bounds).unwrap();
+
+		let mut nodes = data.nodes.keys().copied().collect::<Vec<_>>();
+		nodes.sort_unstable();
+
+		assert_eq!(nodes, vec![1, 2]);
+		assert_eq!(data.ways.keys().collect::<Vec<_>>(), vec![&10]);
+		assert!(data.relations.contains_key(&20));
+
+		let outside = Bounds::new(Coordinate::ZERO, Coordinate::ZERO);
+		assert!(parse_clipped(SAMPLE, &outside).unwrap().is_empty());
+	}
+
+	#[test]
+	fn nested() {
+		let member = |id| Member { member_type: MemberType::Relation, id, role: String::new() };
+		let mut data = parse_str(SAMPLE).unwrap();
+		data.relations.insert(30, Relation { id: 30, members: vec![member(20)], ..Default::default() });
+		data.relations.insert(31, Relation { id: 31, members: vec![member(30)], ..Default::default() });
+		data.relations.insert(32, Relation { id: 32, members: vec![member(99)], ..Default::default() });
+
+		let bounds = Bounds::new(Coordinate::new(41.3036, -81.9018), Coordinate::new(41.3037, -81.9017));
+		data.clip_to_bounds(&bounds);
+
+		let mut relations = data.relations.keys().copied().collect::<Vec<_>>();
+		relations.sort_unstable();
+		assert_eq!(relations, vec![20, 30, 31]);
+	}
+
+	#[test]
+	fn polygon() {
+		let data = parse_str(SAMPLE).unwrap();
+		// triangle around node 3 only, implicitly closed
+		let triangle = [
+			Coordinate::new(41.3039, -81.9023),
+			Coordinate::new(41.3042, -81.9023),
+			Coordinate::new(41.3040, -81.9019),
+		];
+		let clipped = data.clip_to_polygon(&triangle);
+
+		let mut nodes = clipped.nodes.keys().copied().collect::<Vec<_>>();
+		nodes.sort_unstable();
+
+		assert_eq!(nodes, vec![3, 4]);
+		assert_eq!(clipped.ways.keys().collect::<Vec<_>>(), vec![&11]);
+		assert!(clipped.relations.is_empty());
+	}
+}
+//endregion
+
+//region Dedup
+impl OsmData {
+	/// Removes ways with the same node refs and tags as another way, keeping the one with the lowest id.
+	/// Node order matters, unless `match_reversed` is set and a way reversed is considered the same.
+	/// Returns the number of removed ways.
+	pub fn dedup_ways(&mut self, match_reversed: bool) -> usize {
+		let mut ids = self.ways.keys().copied().collect::<Vec<_>>();
+		ids.sort_unstable();
+
+		let mut seen = HashSet::new();
+		let mut duplicates = Vec::new();
+
+		for id in ids {
+			let way = &self.ways[&id];
+
+			let mut nodes = way.nodes.clone();
+			if match_reversed {
+				let reversed = nodes.iter().rev().copied().collect::<Vec<_>>();
+				nodes = nodes.min(reversed);
+			}
+
+			let mut tags = way.tags.iter()
+				.flat_map(|t| t.iter())
+				.map(|(k, v)| (k.clone(), v.clone()))
+				.collect::<Vec<_>>();
+			tags.sort_unstable();
+
+			if !seen.insert((nodes, tags)) {
+				duplicates.push(id);
+			}
+		}
+
+		for id in &duplicates {
+			self.ways.remove(id);
+		}
+		duplicates.len()
+	}
+}
+
+#[cfg(test)]
+mod tests_dedup {
+	use super::*;
+	use crate::Tags;
+
+	#[test]
+	fn dedup() {
+		let tags = Tags::from([("highway".to_string(), "residential".to_string())]);
+		let way = |id, nodes: Vec<Id>, tags: Option<Tags>| (id, Way { id, nodes, tags, ..Default::default() });
+		let data = OsmData {
+			ways: Ways::from_iter([
+				way(1, vec![1, 2, 3], Some(tags.clone())),
+				way(2, vec![1, 2, 3], Some(tags.clone())),
+				way(3, vec![3, 2, 1], Some(tags.clone())),
+				way(4, vec![1, 2, 3], None),
+			]),
+			..Default::default()
+		};
+
+		let mut exact = data.clone();
+		assert_eq!(exact.dedup_ways(false), 1);
+		assert!(!exact.ways.contains_key(&2));
+
+		let mut reversed = data;
+		assert_eq!(reversed.dedup_ways(true), 2);
+		assert_eq!(reversed.ways.len(), 2);
+		assert!(reversed.ways.contains_key(&1) && reversed.ways.contains_key(&4));
+	}
+}
+//endregion
@@ -0,0 +1,856 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{narrow, wide, Bounds, Coordinate, MemberType, OsmData};
+use crate::Float;
+use crate::convert::R;
+use crate::parser::Id;
+
+//region Winding
+/// Orientation of a closed ring, with longitude as x and latitude as y.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Winding {
+	Clockwise,
+	CounterClockwise,
+}
+
+/// Signed shoelace area of a ring, positive for counter-clockwise rings.
+pub(crate) fn signed_area(ring: &[Coordinate]) -> Float {
+	let mut sum = 0.;
+	for pair in ring.windows(2) {
+		sum += pair[0].lon * pair[1].lat - pair[1].lon * pair[0].lat;
+	}
+	sum / 2.
+}
+
+/// Even-odd ray casting test with longitude as x, the ring may be implicitly closed.
+/// Points exactly on an edge may be reported as either inside or outside.
+pub(crate) fn point_in_polygon(point: &Coordinate, ring: &[Coordinate]) -> bool {
+	let mut inside = false;
+	let mut j = ring.len().wrapping_sub(1);
+	for (i, a) in ring.iter().enumerate() {
+		let b = &ring[j];
+		if (a.lat > point.lat) != (b.lat > point.lat)
+			&& point.lon < (b.lon - a.lon) * (point.lat - a.lat) / (b.lat - a.lat) + a.lon {
+			inside = !inside;
+		}
+		j = i;
+	}
+	inside
+}
+
+impl OsmData {
+	/// Determines the [Winding] of a closed way using its signed shoelace area.
+	/// Returns [None] for unknown, open or zero-area ways.
+	pub fn way_winding(&self, way_id: Id) -> Option<Winding> {
+		if !self.ways.get(&way_id)?.is_closed() {
+			return None;
+		}
+
+		let area = signed_area(&self.way_coordinates(way_id)?);
+		if area > 0. {
+			Some(Winding::CounterClockwise)
+		} else if area < 0. {
+			Some(Winding::Clockwise)
+		} else {
+			None
+		}
+	}
+
+	/// Reverses the node refs of a closed way if its [Winding] differs from `desired`.
+	/// Returns `true` if the way was reversed.
+	pub fn enforce_winding(&mut self, way_id: Id, desired: Winding) -> bool {
+		match self.way_winding(way_id) {
+			Some(winding) if winding != desired => {
+				self.ways.get_mut(&way_id).unwrap().nodes.reverse();
+				true
+			}
+			_ => false,
+		}
+	}
+
+	/// Prepares area ways for strict GIS tools that require OGC-valid polygons.
+	///
+	/// Only ways that are areas by [Way::is_area](crate::Way::is_area) without a tags hint are modified,
+	/// so closed lines like roundabouts or `highway` loops keep the node order that gives their direction.
+	/// With `close_tolerance_m`, open ways of at least 3 nodes whose ends are within the tolerance,
+	/// and which would be areas once closed, are closed first by appending their first node.
+	/// All area ways are then treated as exterior rings and made [Winding::CounterClockwise]
+	/// with [OsmData::enforce_winding], holes of multipolygons included.
+	/// Returns the sorted ids of the modified ways.
+	pub fn make_ogc_valid(&mut self, close_tolerance_m: Option<Float>) -> Vec<Id> {
+		let mut ids = self.ways.keys().copied().collect::<Vec<_>>();
+		ids.sort_unstable();
+
+		let mut modified = Vec::new();
+		for id in ids {
+			let mut changed = false;
+
+			if let Some(tolerance) = close_tolerance_m {
+				let way = &self.ways[&id];
+				let ends = way.first_node().and_then(|n| self.coordinate(n))
+					.zip(way.last_node().and_then(|n| self.coordinate(n)));
+
+				if let Some((first, last)) = ends.filter(|_| !way.is_closed() && way.nodes.len() >= 3) {
+					let mut closed = way.clone();
+					closed.nodes.push(closed.nodes[0]);
+
+					if first.distance_to(&last) <= tolerance && closed.is_area(false) {
+						self.ways.insert(id, closed);
+						changed = true;
+					}
+				}
+			}
+
+			if !self.ways[&id].is_area(false) {
+				continue;
+			}
+			if self.enforce_winding(id, Winding::CounterClockwise) || changed {
+				modified.push(id);
+			}
+		}
+		modified
+	}
+}
+
+#[cfg(test)]
+mod tests_winding {
+	use super::*;
+	use super::tests_fixtures::test_data;
+	use crate::{Node, Way};
+
+	fn square(nodes: Vec<Id>) -> OsmData {
+		test_data(&[(0., 0.), (0., 1.), (1., 1.), (1., 0.)], &[&nodes])
+	}
+
+	#[test]
+	fn winding() {
+		assert_eq!(square(vec![1, 2, 3, 4, 1]).way_winding(1), Some(Winding::CounterClockwise));
+		assert_eq!(square(vec![1, 4, 3, 2, 1]).way_winding(1), Some(Winding::Clockwise));
+		assert_eq!(square(vec![1, 2, 3, 4]).way_winding(1), None);
+	}
+
+	#[test]
+	fn enforce() {
+		let mut data = square(vec![1, 4, 3, 2, 1]);
+
+		assert!(data.enforce_winding(1, Winding::CounterClockwise));
+		assert_eq!(data.ways[&1].nodes, vec![1, 2, 3, 4, 1]);
+		assert!(!data.enforce_winding(1, Winding::CounterClockwise));
+	}
+
+	#[test]
+	fn ogc_valid() {
+		let mut data = square(vec![1, 4, 3, 2, 1]);
+		data.nodes.insert(5, Node::from_coordinate(Coordinate::new(0., 0.000001)));
+		data.ways.insert(2, Way { id: 2, nodes: vec![1, 2, 3, 4, 5], ..Default::default() });
+		data.ways.insert(3, Way { id: 3, nodes: vec![1, 2, 3, 4, 1], ..Default::default() });
+		data.ways.insert(4, Way { id: 4, nodes: vec![1, 2, 3], ..Default::default() });
+
+		assert_eq!(data.clone().make_ogc_valid(None), vec![1]);
+		assert_eq!(data.make_ogc_valid(Some(1.)), vec![1, 2]);
+		assert_eq!(data.ways[&1].nodes, vec![1, 2, 3, 4, 1]);
+		assert_eq!(data.ways[&2].nodes, vec![1, 2, 3, 4, 5, 1]);
+		assert_eq!(data.ways[&4].nodes, vec![1, 2, 3]);
+		assert!(data.make_ogc_valid(Some(1.)).is_empty());
+	}
+
+	#[test]
+	fn ogc_valid_lines() {
+		let mut data = square(vec![1, 4, 3, 2, 1]);
+		data.ways.get_mut(&1).unwrap().tags = Some(crate::structs::tests_tags::tags(&[("junction", "roundabout")]));
+		data.nodes.insert(5, Node::from_coordinate(Coordinate::new(0., 0.000001)));
+		data.ways.insert(2, Way {
+			id: 2,
+			nodes: vec![1, 2, 3, 4, 5],
+			tags: Some(crate::structs::tests_tags::tags(&[("highway", "residential")])),
+			..Default::default()
+		});
+
+		assert!(data.make_ogc_valid(Some(1.)).is_empty());
+		assert_eq!(data.ways[&1].nodes, vec![1, 4, 3, 2, 1]);
+		assert_eq!(data.ways[&2].nodes, vec![1, 2, 3, 4, 5]);
+	}
+}
+//endregion
+
+//region Contains
+/// Returns `true` if `point` is exactly on one of the segments of `ring`, vertices included.
+/// Without a tolerance, points that round off a sloped edge aren't detected.
+fn on_boundary(point: &Coordinate, ring: &[Coordinate]) -> bool {
+	ring.windows(2).any(|pair| {
+		let (a, b) = (&pair[0], &pair[1]);
+		cross(a, b, point) == 0.
+			&& (a.lat.min(b.lat)..=a.lat.max(b.lat)).contains(&point.lat)
+			&& (a.lon.min(b.lon)..=a.lon.max(b.lon)).contains(&point.lon)
+	})
+}
+
+impl OsmData {
+	/// Ids of the closed ways whose ring contains `coord`, sorted ascending, e.g. the buildings or landuse at a location.
+	/// Uses even-odd ray casting, skipping rings whose bounds don't contain `coord`.
+	/// Points on an edge or vertex count as inside, but this is only guaranteed for points that are
+	/// exactly representable on the edge, e.g. on axis-aligned edges or vertices, since it's checked without tolerance.
+	/// Ways that reference missing nodes are skipped.
+	pub fn polygons_containing(&self, coord: &Coordinate) -> Vec<Id> {
+		let contains = |ring: Vec<Coordinate>| {
+			let mut bounds = Bounds::EMPTY;
+			for c in &ring {
+				bounds.extend(c);
+			}
+			bounds.contains(coord) && (on_boundary(coord, &ring) || point_in_polygon(coord, &ring))
+		};
+
+		let mut ids = self.ways.values()
+			.filter(|w| w.is_closed())
+			.filter(|w| self.way_coordinates(w.id).is_some_and(contains))
+			.map(|w| w.id)
+			.collect::<Vec<_>>();
+		ids.sort_unstable();
+		ids
+	}
+}
+
+#[cfg(test)]
+mod tests_contains {
+	use super::*;
+	use super::tests_fixtures::test_data;
+
+	#[test]
+	fn polygons_containing() {
+		// a 2x2 square with a triangle in its lower half and a missing node in way 3
+		let data = test_data(
+			&[(0., 0.), (0., 2.), (2., 2.), (2., 0.), (1., 1.)],
+			&[&[1, 2, 3, 4, 1], &[1, 2, 5, 1], &[1, 2, 6, 1], &[1, 2, 3, 4]],
+		);
+
+		assert_eq!(data.polygons_containing(&Coordinate::new(0.5, 1.)), vec![1, 2]);
+		assert_eq!(data.polygons_containing(&Coordinate::new(1.5, 1.)), vec![1]);
+		// within the bounds of way 2 but outside of it
+		assert_eq!(data.polygons_containing(&Coordinate::new(0.9, 0.1)), vec![1]);
+		// on the shared edge and vertex, and on the edge of way 1 only
+		assert_eq!(data.polygons_containing(&Coordinate::new(0., 1.)), vec![1, 2]);
+		assert_eq!(data.polygons_containing(&Coordinate::new(0., 0.)), vec![1, 2]);
+		assert_eq!(data.polygons_containing(&Coordinate::new(2., 1.)), vec![1]);
+		assert!(data.polygons_containing(&Coordinate::new(3., 1.)).is_empty());
+	}
+}
+//endregion
+
+//region Multipolygon
+/// Joins node id sequences that share endpoints into closed rings.
+/// Returns [None] if any sequence can't be closed.
+fn assemble_rings(mut segments: Vec<Vec<Id>>) -> Option<Vec<Vec<Id>>> {
+	let mut rings = Vec::new();
+
+	while let Some(mut ring) = segments.pop() {
+		while ring.len() < 4 || ring.first() != ring.last() {
+			let end = *ring.last()?;
+			let i = segments.iter().position(|s| s.first() == Some(&end) || s.last() == Some(&end))?;
+
+			let mut next = segments.swap_remove(i);
+			if next.first() != Some(&end) {
+				next.reverse();
+			}
+			ring.extend_from_slice(&next[1..]);
+		}
+		rings.push(ring);
+	}
+
+	Some(rings)
+}
+
+impl OsmData {
+	/// Assembles the `outer` and `inner` member ways of a multipolygon relation into closed rings.
+	/// Member ways may be in any order and direction, ways sharing endpoints are joined.
+	///
+	/// All outer rings are returned first, followed by all inner rings.
+	/// The assembly is all-or-nothing: if the relation or any member way or node is missing,
+	/// or any ring can't be closed, [None] is returned instead of a partial result.
+	pub fn relation_polygon(&self, rel_id: Id) -> Option<Vec<Vec<Coordinate>>> {
+		let relation = self.relations.get(&rel_id)?;
+		let mut rings = Vec::new();
+
+		for role in ["outer", "inner"] {
+			let segments = relation.members.iter()
+				.filter(|m| m.member_type == MemberType::Way && m.role == role)
+				.map(|m| self.ways.get(&m.id).map(|w| w.nodes.clone()))
+				.collect::<Option<Vec<_>>>()?;
+
+			for ring in assemble_rings(segments)? {
+				rings.push(ring.iter()
+					.map(|id| self.coordinate(*id))
+					.collect::<Option<Vec<_>>>()?);
+			}
+		}
+
+		Some(rings)
+	}
+}
+
+#[cfg(test)]
+mod tests_multipolygon {
+	use super::*;
+	use super::tests_fixtures::test_data;
+	use crate::{Member, Relation, Relations, Way, Ways};
+
+	fn data(ways: Vec<(Id, Vec<Id>, &str)>) -> OsmData {
+		let coordinates = (1..=8).map(|i| (i as Float, 0.)).collect::<Vec<_>>();
+
+		let members = ways.iter()
+			.map(|(id, _, role)| Member { member_type: MemberType::Way, id: *id, role: role.to_string() })
+			.collect();
+
+		OsmData {
+			ways: ways.into_iter()
+				.map(|(id, nodes, _)| (id, Way { id, nodes, ..Default::default() }))
+				.collect::<Ways>(),
+			relations: Relations::from_iter([(1, Relation { id: 1, members, ..Default::default() })]),
+			..test_data(&coordinates, &[])
+		}
+	}
+
+	#[test]
+	fn assemble() {
+		let data = data(vec![
+			(10, vec![1, 2, 3], "outer"),
+			(11, vec![1, 4], "outer"),
+			(12, vec![3, 4], "outer"),
+			(13, vec![5, 6, 7, 5], "inner"),
+		]);
+
+		let rings = data.relation_polygon(1).unwrap();
+		assert_eq!(rings.len(), 2);
+		assert_eq!(rings[0].len(), 5);
+		assert_eq!(rings[0].first(), rings[0].last());
+		assert_eq!(rings[1].len(), 4);
+	}
+
+	#[test]
+	fn unclosed() {
+		let data = data(vec![
+			(10, vec![1, 2, 3], "outer"),
+			(11, vec![3, 4], "outer"),
+		]);
+
+		assert_eq!(data.relation_polygon(1), None);
+		assert_eq!(data.relation_polygon(2), None);
+	}
+}
+//endregion
+
+//region Hull
+/// Cross product of `o -> a` and `o -> b`, positive for a counter-clockwise turn.
+fn cross(o: &Coordinate, a: &Coordinate, b: &Coordinate) -> Float {
+	(a.lon - o.lon) * (b.lat - o.lat) - (a.lat - o.lat) * (b.lon - o.lon)
+}
+
+/// Andrew's monotone chain with longitude as x and latitude as y, see [OsmData::convex_hull].
+fn hull(mut points: Vec<Coordinate>) -> Vec<Coordinate> {
+	points.sort_unstable_by(|a, b| a.lon.total_cmp(&b.lon).then(a.lat.total_cmp(&b.lat)));
+	points.dedup();
+
+	if points.len() < 3 {
+		return points;
+	}
+
+	let mut hull: Vec<Coordinate> = Vec::with_capacity(points.len() + 1);
+	for pass in [points.iter().collect::<Vec<_>>(), points.iter().rev().collect()] {
+		let start = hull.len();
+		for p in pass {
+			while hull.len() >= start + 2 && cross(&hull[hull.len() - 2], &hull[hull.len() - 1], p) <= 0. {
+				hull.pop();
+			}
+			hull.push(*p);
+		}
+		// the last point of each chain is the first of the other
+		hull.pop();
+	}
+	hull
+}
+
+impl OsmData {
+	/// Computes the convex hull of all node coordinates in counter-clockwise order, without repeating the first point.
+	///
+	/// Uses Andrew's monotone chain with longitude as x and latitude as y,
+	/// a planar approximation that is fine for extracts not crossing the antimeridian.
+	/// With fewer than 3 distinct points, those are returned directly, collinear points are dropped.
+	pub fn convex_hull(&self) -> Vec<Coordinate> {
+		hull(self.nodes.values().map(|n| n.pos).collect())
+	}
+
+	/// Computes the minimum-area rectangle enclosing a [Way](crate::Way), e.g. for the orientation of a building.
+	/// The corners are in counter-clockwise order.
+	///
+	/// The rectangle is found by trying every edge direction of the convex hull, in a local planar approximation
+	/// with longitudes scaled by the cosine of the mean latitude, so its angles are right in meters.
+	/// Returns [None] if the way doesn't exist, references a missing node,
+	/// or has fewer than 3 distinct points that aren't all collinear.
+	pub fn way_oriented_bbox(&self, way_id: Id) -> Option<[Coordinate; 4]> {
+		let coordinates = self.way_coordinates(way_id)?;
+		let lat0 = coordinates.iter().map(|c| wide(c.lat)).sum::<f64>() / coordinates.len() as f64;
+		let k = lat0.to_radians().cos();
+
+		let hull = hull(coordinates.iter().map(|c| Coordinate::new(c.lat, narrow(wide(c.lon) * k))).collect());
+		if hull.len() < 3 {
+			return None;
+		}
+		let points = hull.iter().map(|c| (wide(c.lon), wide(c.lat))).collect::<Vec<_>>();
+
+		// The minimum rectangle has a side on a hull edge, so try each edge direction.
+		let mut best: Option<(f64, [(f64, f64); 4])> = None;
+		for (i, a) in points.iter().enumerate() {
+			let b = points[(i + 1) % points.len()];
+			let len = (b.0 - a.0).hypot(b.1 - a.1);
+			let (ux, uy) = ((b.0 - a.0) / len, (b.1 - a.1) / len);
+
+			let (mut min_u, mut max_u, mut min_v, mut max_v) = (f64::MAX, f64::MIN, f64::MAX, f64::MIN);
+			for p in &points {
+				let (u, v) = (p.0 * ux + p.1 * uy, p.1 * ux - p.0 * uy);
+				(min_u, max_u) = (min_u.min(u), max_u.max(u));
+				(min_v, max_v) = (min_v.min(v), max_v.max(v));
+			}
+
+			let area = (max_u - min_u) * (max_v - min_v);
+			if best.as_ref().is_none_or(|(a, _)| area < *a) {
+				let corner = |u: f64, v: f64| (u * ux - v * uy, u * uy + v * ux);
+				best = Some((area, [
+					corner(min_u, min_v),
+					corner(max_u, min_v),
+					corner(max_u, max_v),
+					corner(min_u, max_v),
+				]));
+			}
+		}
+
+		let (_, corners) = best?;
+		Some(corners.map(|(x, y)| Coordinate::new(narrow(y), narrow(x / k))))
+	}
+}
+
+#[cfg(test)]
+mod tests_hull {
+	use super::*;
+	use super::tests_fixtures::test_data;
+
+	fn data(coordinates: &[(Float, Float)]) -> OsmData {
+		test_data(coordinates, &[])
+	}
+
+	#[test]
+	fn hull() {
+		let hull = data(&[(0., 0.), (0., 2.), (2., 2.), (2., 0.), (1., 1.), (0., 1.)]).convex_hull();
+
+		assert_eq!(hull, vec![
+			Coordinate::new(0., 0.),
+			Coordinate::new(0., 2.),
+			Coordinate::new(2., 2.),
+			Coordinate::new(2., 0.),
+		]);
+		assert!(signed_area(&[hull.clone(), vec![hull[0]]].concat()) > 0.);
+	}
+
+	#[test]
+	fn degenerate() {
+		assert!(data(&[]).convex_hull().is_empty());
+		assert_eq!(data(&[(1., 1.), (1., 1.)]).convex_hull(), vec![Coordinate::new(1., 1.)]);
+		assert_eq!(data(&[(0., 0.), (1., 1.), (2., 2.)]).convex_hull().len(), 2);
+	}
+
+	#[test]
+	fn oriented_bbox() {
+		// a square of about 157 m rotated by 45 degrees at 60° north, plus an inner point
+		let (d, k) = (0.001, 0.5);
+		let mut data = test_data(
+			&[(0., 0.), (d, d / k), (2. * d, 0.), (d, -d / k), (d, 0.)],
+			&[&[1, 2, 3, 4, 5, 1], &[1, 5, 3]],
+		);
+		for node in data.nodes.values_mut() {
+			node.pos.lat += 60.;
+		}
+
+		let corners = data.way_oriented_bbox(1).unwrap();
+		for id in 1..=4 {
+			assert!(corners.iter().any(|c| c.approx_eq(&data.nodes[&id].pos, 1e-5)), "{corners:?} misses {id}");
+		}
+		let ring = [corners.to_vec(), vec![corners[0]]].concat();
+		assert!(signed_area(&ring) > 0.);
+
+		assert_eq!(data.way_oriented_bbox(2), None);
+		assert_eq!(data.way_oriented_bbox(3), None);
+	}
+}
+//endregion
+
+//region Length
+impl OsmData {
+	/// Length of a [Way](crate::Way) in meters, summing the great-circle distances between its nodes.
+	/// Returns [None] if the way doesn't exist or references a missing node.
+	pub fn way_length(&self, way_id: Id) -> Option<Float> {
+		let length = self.way_coordinates(way_id)?
+			.windows(2)
+			.map(|pair| wide(pair[0].distance_to(&pair[1])))
+			.sum();
+		Some(narrow(length))
+	}
+}
+
+#[cfg(test)]
+mod tests_length {
+	use crate::parse_str;
+	use crate::parser::tests_parser::SAMPLE;
+
+	#[test]
+	fn length() {
+		let data = parse_str(SAMPLE).unwrap();
+
+		// 0.00088° of latitude, f32 coordinates are only exact to about 0.2 m here
+		assert!((data.way_length(10).unwrap() - 97.98).abs() < 0.5);
+		assert_eq!(data.way_length(12), None);
+	}
+}
+//endregion
+
+//region Hausdorff
+/// Largest distance in meters from a point of `a` to its nearest point of `b`.
+fn directed_hausdorff(a: &[Coordinate], b: &[Coordinate]) -> f64 {
+	a.iter()
+		.map(|p| b.iter().map(|q| wide(p.distance_to(q))).fold(f64::INFINITY, f64::min))
+		.fold(0., f64::max)
+}
+
+impl OsmData {
+	/// Discrete Hausdorff distance in meters between the nodes of two ways,
+	/// the farthest any node of one way is from the nearest node of the other.
+	/// Small values mean the ways likely represent the same feature.
+	/// Returns [None] if either way doesn't exist, references a missing node or has no nodes.
+	pub fn way_hausdorff(&self, a: Id, b: Id) -> Option<Float> {
+		let a = self.way_coordinates(a)?;
+		let b = self.way_coordinates(b)?;
+		if a.is_empty() || b.is_empty() {
+			return None;
+		}
+
+		Some(narrow(directed_hausdorff(&a, &b).max(directed_hausdorff(&b, &a))))
+	}
+}
+
+#[cfg(test)]
+mod tests_hausdorff {
+	use super::tests_fixtures::test_data;
+
+	#[test]
+	fn hausdorff() {
+		let data = test_data(
+			&[(0., 0.), (0., 0.001), (0.001, 0.), (0.001, 0.001), (0.001, 0.002)],
+			&[&[1, 2], &[3, 4, 5], &[]],
+		);
+
+		// node 5 is about 157 m from node 2, while way 1 is only 111 m from way 2
+		let distance = data.way_hausdorff(1, 2).unwrap();
+		assert!((distance - 157.25).abs() < 0.5);
+		assert_eq!(data.way_hausdorff(2, 1), Some(distance));
+		assert_eq!(data.way_hausdorff(1, 1), Some(0.));
+		assert_eq!(data.way_hausdorff(1, 3), None);
+		assert_eq!(data.way_hausdorff(1, 4), None);
+	}
+}
+//endregion
+
+//region Turn
+impl OsmData {
+	/// Sum of the absolute bearing changes in degrees between consecutive segments of a [Way](crate::Way),
+	/// low for straight roads and high for twisty paths. Repeated coordinates are skipped.
+	/// Returns [None] if the way doesn't exist or references a missing node, ways with fewer than three nodes have `0.0`.
+	pub fn way_total_turn(&self, way_id: Id) -> Option<Float> {
+		let mut coordinates = self.way_coordinates(way_id)?;
+		coordinates.dedup();
+
+		let bearings = coordinates.windows(2)
+			.map(|pair| wide(pair[0].bearing_to(&pair[1])))
+			.collect::<Vec<_>>();
+
+		let turn = bearings.windows(2)
+			.map(|pair| ((pair[1] - pair[0] + 540.) % 360. - 180.).abs())
+			.sum();
+		Some(narrow(turn))
+	}
+}
+
+#[cfg(test)]
+mod tests_turn {
+	use super::tests_fixtures::test_data;
+
+	#[test]
+	fn total_turn() {
+		// a zigzag with a right angle at node 2 and a left angle at node 3
+		let data = test_data(&[(0., 0.), (0., 0.001), (-0.001, 0.001), (-0.001, 0.002)], &[&[1, 2, 2, 3, 4], &[1, 2]]);
+
+		assert!((data.way_total_turn(1).unwrap() - 180.).abs() < 0.01);
+		assert_eq!(data.way_total_turn(2), Some(0.));
+		assert_eq!(data.way_total_turn(3), None);
+	}
+}
+//endregion
+
+//region Simplify
+/// Distance in meters from `p` to the segment `a`-`b`, all in local planar meters.
+fn segment_distance(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+	let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+	let len2 = dx * dx + dy * dy;
+	let t = if len2 == 0. { 0. } else { (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len2).clamp(0., 1.) };
+
+	(p.0 - a.0 - t * dx).hypot(p.1 - a.1 - t * dy)
+}
+
+/// Projects `points` to local planar meters around their mean latitude.
+fn planar(points: &[Coordinate]) -> Vec<(f64, f64)> {
+	let lat0 = points.iter().map(|c| wide(c.lat)).sum::<f64>() / points.len() as f64;
+	let scale = wide(R) * std::f64::consts::PI / 180.;
+	points.iter()
+		.map(|c| (wide(c.lon) * scale * lat0.to_radians().cos(), wide(c.lat) * scale))
+		.collect()
+}
+
+/// Index of the point in `start + 1..end` farthest from the segment `a`-`b`.
+fn farthest(planar: &[(f64, f64)], start: usize, end: usize, a: (f64, f64), b: (f64, f64)) -> Option<(usize, f64)> {
+	(start + 1..end)
+		.map(|i| (i, segment_distance(planar[i], a, b)))
+		.max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Douglas-Peucker over `planar` points, marking kept points in `keep`.
+/// Points already marked are kept and split the line like endpoints do.
+fn douglas_peucker(planar: &[(f64, f64)], tolerance_m: f64, keep: &mut [bool]) {
+	let pinned = keep.iter().enumerate().filter(|(_, k)| **k).map(|(i, _)| i).collect::<Vec<_>>();
+	let mut stack = pinned.windows(2).map(|w| (w[0], w[1])).collect::<Vec<_>>();
+
+	while let Some((start, end)) = stack.pop() {
+		if let Some((i, distance)) = farthest(planar, start, end, planar[start], planar[end]) {
+			if distance > tolerance_m {
+				keep[i] = true;
+				stack.push((start, i));
+				stack.push((i, end));
+			}
+		}
+	}
+}
+
+impl OsmData {
+	/// Simplifies a [Way](crate::Way) with the Douglas-Peucker algorithm, keeping its endpoints.
+	/// Distances are measured in meters on a local planar approximation around the way.
+	///
+	/// Closed ways have no line between their endpoints to measure against, so the node farthest from
+	/// the start and the node farthest from that diagonal are kept too, leaving a ring of at least 4 refs.
+	///
+	/// Returns the ids of the kept nodes in order,
+	/// or [None] if the way doesn't exist or references a missing node.
+	pub fn simplify_way(&self, way_id: Id, tolerance_m: Float) -> Option<Vec<Id>> {
+		self.simplify_way_pinned(way_id, tolerance_m, |_| false)
+	}
+
+	/// [OsmData::simplify_way], additionally keeping all nodes that are `pinned`.
+	fn simplify_way_pinned(&self, way_id: Id, tolerance_m: Float, pinned: impl Fn(Id) -> bool) -> Option<Vec<Id>> {
+		let way = self.ways.get(&way_id)?;
+		let coordinates = self.way_coordinates(way_id)?;
+		if way.nodes.len() < 3 {
+			return Some(way.nodes.clone());
+		}
+
+		let planar = planar(&coordinates);
+		let last = way.nodes.len() - 1;
+		let mut keep = way.nodes.iter()
+			.enumerate()
+			.map(|(i, id)| i == 0 || i == last || pinned(*id))
+			.collect::<Vec<_>>();
+
+		if way.is_closed() && way.nodes.len() >= 4 {
+			let start = planar[0];
+			if let Some((far, _)) = farthest(&planar, 0, last, start, start) {
+				keep[far] = true;
+				let other = farthest(&planar, 0, far, start, planar[far])
+					.into_iter()
+					.chain(farthest(&planar, far, last, start, planar[far]))
+					.max_by(|a, b| a.1.total_cmp(&b.1));
+				if let Some((i, _)) = other {
+					keep[i] = true;
+				}
+			}
+		}
+		douglas_peucker(&planar, wide(tolerance_m), &mut keep);
+
+		Some(way.nodes.iter()
+			.zip(keep)
+			.filter(|(_, keep)| *keep)
+			.map(|(id, _)| *id)
+			.collect())
+	}
+
+	/// Simplifies every way with [OsmData::simplify_way] and removes the nodes that were dropped.
+	///
+	/// Endpoints, tagged nodes, nodes shared by several ways, or repeated within a way, are always kept,
+	/// and so are nodes that are relation members. Ways referencing missing nodes are left unchanged.
+	/// Returns the number of nodes before and after.
+	pub fn simplify(&mut self, tolerance_m: Float) -> (usize, usize) {
+		let before = self.nodes.len();
+
+		let mut refs = HashMap::<Id, usize>::new();
+		for way in self.ways.values() {
+			for node in &way.nodes {
+				*refs.entry(*node).or_default() += 1;
+			}
+		}
+		let members = self.relations.values()
+			.flat_map(|r| &r.members)
+			.filter(|m| m.member_type == MemberType::Node)
+			.map(|m| m.id)
+			.collect::<HashSet<_>>();
+
+		let pinned = |id: Id| {
+			refs.get(&id).is_some_and(|n| *n > 1)
+				|| members.contains(&id)
+				|| self.nodes.get(&id).and_then(|n| n.tags.as_ref()).is_some_and(|t| !t.is_empty())
+		};
+
+		let simplified = self.ways.keys()
+			.filter_map(|id| Some((*id, self.simplify_way_pinned(*id, tolerance_m, pinned)?)))
+			.collect::<Vec<_>>();
+
+		for (id, nodes) in simplified {
+			let way = self.ways.get_mut(&id).unwrap();
+			let kept = nodes.iter().collect::<HashSet<_>>();
+			for dropped in way.nodes.iter().filter(|n| !kept.contains(n)) {
+				self.nodes.remove(dropped);
+			}
+			way.nodes = nodes;
+		}
+
+		(before, self.nodes.len())
+	}
+}
+
+#[cfg(test)]
+mod tests_simplify {
+	use super::*;
+	use super::tests_fixtures::test_data;
+	use crate::Tags;
+
+	/// A line along the equator with a peak of about 110 m at node 3,
+	/// node 2 is exactly on the way up and node 4 about 1 m off the way down, and an unrelated node 6.
+	fn data() -> OsmData {
+		test_data(
+			&[(0., 0.), (0.0005, 0.001), (0.001, 0.002), (0.00051, 0.003), (0., 0.004), (1., 1.)],
+			&[&[1, 2, 3, 4, 5]],
+		)
+	}
+
+	#[test]
+	fn simplify_way() {
+		let data = data();
+
+		assert_eq!(data.simplify_way(1, 10.), Some(vec![1, 3, 5]));
+		assert_eq!(data.simplify_way(1, 0.1), Some(vec![1, 3, 4, 5]));
+		assert_eq!(data.simplify_way(1, 10_000.), Some(vec![1, 5]));
+		assert_eq!(data.simplify_way(2, 10.), None);
+	}
+
+	#[test]
+	fn simplify_ring() {
+		// a square of about 110 m with an extra node in the middle of three sides
+		let mut data = test_data(
+			&[(0., 0.), (0., 0.0005), (0., 0.001), (0.0005, 0.001), (0.001, 0.001), (0.001, 0.0005), (0.001, 0.)],
+			&[&[1, 2, 3, 4, 5, 6, 7, 1]],
+		);
+
+		assert_eq!(data.simplify_way(1, 10.), Some(vec![1, 3, 5, 7, 1]));
+		// the whole ring is within the tolerance, but stays a polygon
+		assert_eq!(data.simplify_way(1, 10_000.).map(|n| n.len()), Some(4));
+
+		assert_eq!(data.simplify(10_000.), (7, 3));
+		assert_eq!(data.ways[&1].nodes.len(), 4);
+		assert!(data.ways[&1].is_closed());
+	}
+
+	#[test]
+	fn simplify() {
+		let mut data = data();
+		data.nodes.get_mut(&4).unwrap().tags = Some(Tags::from([("barrier".to_string(), "gate".to_string())]));
+
+		assert_eq!(data.simplify(10.), (6, 5));
+		assert_eq!(data.ways[&1].nodes, vec![1, 3, 4, 5]);
+		// unrelated nodes are kept
+		assert!(data.nodes.contains_key(&6));
+	}
+}
+//endregion
+
+//region Densify
+impl OsmData {
+	/// Resolves a [Way](crate::Way) to its coordinates with points inserted along the great circle,
+	/// so that no segment is longer than `max_segment_m` meters.
+	/// A non-positive `max_segment_m` leaves the coordinates unchanged.
+	///
+	/// Returns [None] if the way doesn't exist or references a missing node.
+	pub fn densify_way(&self, way_id: Id, max_segment_m: Float) -> Option<Vec<Coordinate>> {
+		let coordinates = self.way_coordinates(way_id)?;
+		if max_segment_m <= 0. || coordinates.len() < 2 {
+			return Some(coordinates);
+		}
+
+		let mut dense = vec![coordinates[0]];
+		for pair in coordinates.windows(2) {
+			let distance = pair[0].distance_to(&pair[1]);
+			let bearing = pair[0].bearing_to(&pair[1]);
+			let steps = (distance / max_segment_m).ceil() as usize;
+
+			for i in 1..steps {
+				dense.push(pair[0].destination(bearing, distance * i as Float / steps as Float));
+			}
+			dense.push(pair[1]);
+		}
+
+		Some(dense)
+	}
+}
+
+#[cfg(test)]
+mod tests_densify {
+	use super::*;
+	use super::tests_fixtures::test_data;
+
+	#[test]
+	fn densify() {
+		let data = test_data(&[(0., 0.), (0., 0.01), (0., 0.0105)], &[&[1, 2, 3]]);
+
+		// about 1113 m and 56 m
+		let dense = data.densify_way(1, 300.).unwrap();
+		assert_eq!(dense.len(), 6);
+		assert!(dense.windows(2).all(|pair| pair[0].distance_to(&pair[1]) <= 300.));
+		assert!(dense[2].approx_eq(&Coordinate::new(0., 0.005), 1e-6));
+		assert_eq!(dense[4], Coordinate::new(0., 0.01));
+
+		assert_eq!(data.densify_way(1, 0.).unwrap().len(), 3);
+		assert_eq!(data.densify_way(2, 300.), None);
+	}
+}
+//endregion
+
+//region Fixtures
+#[cfg(test)]
+pub(crate) mod tests_fixtures {
+	use super::*;
+	use crate::{Node, Way};
+
+	/// Test data with nodes numbered from 1 at `coordinates`, and ways numbered from 1 referencing them.
+	pub(crate) fn test_data(coordinates: &[(Float, Float)], ways: &[&[Id]]) -> OsmData {
+		OsmData {
+			nodes: (1..).zip(coordinates)
+				.map(|(id, (lat, lon))| (id, Node { id, pos: Coordinate::new(*lat, *lon), ..Default::default() }))
+				.collect(),
+			ways: (1..).zip(ways)
+				.map(|(id, nodes)| (id, Way { id, nodes: nodes.to_vec(), ..Default::default() }))
+				.collect(),
+			..Default::default()
+		}
+	}
+}
+//endregion
@@ -1,9 +1,45 @@
+//! Parser for OpenStreetMap data in the JSON format of the API, with optional PBF support.
+//!
+//! The `minimal` feature is not additive: it removes the timestamp, version, changeset,
+//! user and uid fields of [Node] and [Way], and `Node::with_timestamp`. Since Cargo unifies
+//! features, enabling it anywhere breaks all crates in the build that use them,
+//! so it should only be enabled by the final binary. [OsmData::contributors] is empty with it.
+
 mod parser;
 mod structs;
+mod error;
+mod builder;
+mod geometry;
+mod topology;
+mod filter;
+mod spatial;
+mod stats;
+mod export;
+#[cfg(feature = "pbf")] mod pbf;
+#[cfg(feature = "roaring")] mod selection;
 pub mod convert;
+pub mod tags;
+pub mod diff;
+pub mod prelude;
 
 pub use parser::*;
 pub use structs::*;
+pub use error::*;
+pub use builder::*;
+pub use geometry::*;
+pub use topology::*;
+pub use stats::*;
+pub use export::*;
+#[cfg(feature = "pbf")] pub use pbf::*;
+#[cfg(feature = "roaring")] pub use selection::*;
 
 #[cfg(feature = "f64")] type Float = f64;
 #[cfg(not(feature = "f64"))] type Float = f32;
+
+/// Widens a [Float] to `f64` for precision-sensitive intermediate math.
+#[allow(clippy::unnecessary_cast)]
+pub(crate) const fn wide(x: Float) -> f64 { x as f64 }
+
+/// Narrows an intermediate `f64` result back to [Float].
+#[allow(clippy::unnecessary_cast)]
+pub(crate) const fn narrow(x: f64) -> Float { x as Float }
@@ -1,46 +1,641 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use serde::de::DeserializeOwned;
+use crate::error::{ElementError, OsmError};
 use crate::structs::*;
 
 pub type Id = u64;
-pub type Nodes = HashMap<Id, Node>;
-pub type Ways = HashMap<Id, Way>;
-pub type Tags = HashMap<String, String>;
+
+#[cfg(feature = "rustc_hash")] type IdHasher = rustc_hash::FxBuildHasher;
+#[cfg(not(feature = "rustc_hash"))] type IdHasher = std::collections::hash_map::RandomState;
+
+/// Map keyed by element [Id], an `FxHashMap` with the `rustc_hash` feature.
+pub type IdMap<V> = HashMap<Id, V, IdHasher>;
+pub type Nodes = IdMap<Node>;
+pub type Ways = IdMap<Way>;
+pub type Relations = IdMap<Relation>;
+
+/// Constructors for [Nodes], [Ways] and [Relations] that work with and without the `rustc_hash` feature,
+/// since `FxHashMap` has no `new` or `with_capacity`.
+pub trait NodeMap {
+	fn new() -> Self;
+	fn with_capacity(capacity: usize) -> Self;
+}
+
+impl<V> NodeMap for IdMap<V> {
+	fn new() -> Self {
+		Self::default()
+	}
+
+	fn with_capacity(capacity: usize) -> Self {
+		Self::with_capacity_and_hasher(capacity, IdHasher::default())
+	}
+}
 
 
 /// Parse JSON data from an .osm file aquired trough https://wiki.openstreetmap.org/wiki/API_v0.6#Retrieving_map_data_by_bounding_box:_GET_/api/0.6/map.
-pub fn parse(path: &str) -> Result<OsmData, Box<dyn std::error::Error>> {
-	let file = std::fs::read_to_string(path).unwrap();
-	let raw = serde_json::from_str::<RawOsmData>(&file)?;
+pub fn parse(path: &str) -> Result<OsmData, OsmError> {
+	let file = std::fs::read_to_string(path)?;
+	parse_str(&file)
+}
 
-	let mut nodes = Nodes::new();
-	let mut ways = Ways::new();
+/// Parse JSON data already loaded into memory, see [parse].
+pub fn parse_str(json: &str) -> Result<OsmData, OsmError> {
+	let raw = serde_json::from_str::<RawOsmData>(json)?;
+	raw.try_into()
+}
 
-	for e in raw.elements {
-		let t = e["type"].as_str().ok_or("\"type\" is not a string")?;
-		match t {
-			"node" => {
-				let node = serde_json::from_value::<RawNode>(e)?;
-				nodes.insert(node.id, node.into());
-			}
-			"way" => {
-				let way = serde_json::from_value::<Way>(e)?;
-				ways.insert(way.id, way);
-			}
-			"relation" => {
-				// relations are not supported
-			}
-			_ => Err("invalid type")?,
+/// Parse JSON data like [parse], but from a memory-mapped file instead of reading it into a [String].
+/// This lowers peak memory for large files, especially when the OS already caches them.
+///
+/// The file must not be modified while it is parsed, e.g. by another process,
+/// since that is undefined behavior for memory maps.
+#[cfg(feature = "memmap")]
+pub fn parse_mmap<P: AsRef<std::path::Path>>(path: P) -> Result<OsmData, OsmError> {
+	let file = std::fs::File::open(path)?;
+	// SAFETY: the map is only read while parsing, the caller guarantees it isn't modified meanwhile.
+	let map = unsafe { memmap2::Mmap::map(&file)? };
+	let raw = serde_json::from_slice::<RawOsmData>(&map)?;
+	raw.try_into()
+}
+
+/// Parse JSON data from an async reader, e.g. a streamed API response, see [parse_str].
+/// The whole input is buffered before parsing.
+#[cfg(feature = "async")]
+pub async fn parse_async<R: tokio::io::AsyncRead + Unpin>(mut reader: R) -> Result<OsmData, OsmError> {
+	use tokio::io::AsyncReadExt;
+
+	let mut json = String::new();
+	reader.read_to_string(&mut json).await?;
+	parse_str(&json)
+}
+
+/// Parse JSON data from a reader like [parse_str], transparently decompressing it
+/// if it starts with the gzip magic bytes `1f 8b`. The whole input is buffered before parsing.
+#[cfg(feature = "gzip")]
+pub fn parse_auto<R: std::io::Read>(mut reader: R) -> Result<OsmData, OsmError> {
+	use std::io::{ErrorKind, Read};
+
+	// a single read may return less than both bytes
+	let mut magic = [0; 2];
+	let mut len = 0;
+	while len < magic.len() {
+		match reader.read(&mut magic[len..]) {
+			Ok(0) => break,
+			Ok(n) => len += n,
+			Err(e) if e.kind() == ErrorKind::Interrupted => {}
+			Err(e) => return Err(e.into()),
 		}
 	}
-	
+
+	// put the peeked bytes back in front of the rest
+	let mut reader = (&magic[..len]).chain(reader);
+	let mut json = String::new();
+	if magic[..len] == [0x1f, 0x8b] {
+		flate2::read::MultiGzDecoder::new(reader).read_to_string(&mut json)?;
+	} else {
+		reader.read_to_string(&mut json)?;
+	}
+	parse_str(&json)
+}
+
+/// Parse JSON data like [parse_str] and clip it with [OsmData::clip_to_bounds].
+/// Ways touching `bounds` are kept whole, even if some of their nodes lie outside.
+///
+/// This is only a convenience wrapper, the whole input is parsed before clipping,
+/// so peak memory is the same as with [parse_str].
+pub fn parse_clipped(json: &str, bounds: &Bounds) -> Result<OsmData, OsmError> {
+	let mut data = parse_str(json)?;
+	data.clip_to_bounds(bounds);
+	Ok(data)
+}
+
+/// Parse JSON data like [parse_str], but skip elements that fail to convert instead of aborting.
+/// The errors of all skipped elements are returned alongside the remaining data.
+/// If the document itself is invalid, the returned [OsmData] is empty.
+pub fn parse_lossy(json: &str) -> (OsmData, Vec<OsmError>) {
+	let raw = match serde_json::from_str::<RawOsmData>(json) {
+		Ok(raw) => raw,
+		Err(e) => return (OsmData::default(), vec![e.into()]),
+	};
+
+	let mut errors = Vec::new();
+	let data = convert(raw, &mut Options::default(), |e| {
+		errors.push(e);
+		Ok(())
+	}).expect("lossy conversion never fails");
+
+	(data, errors)
+}
+
+impl TryFrom<RawOsmData> for OsmData {
+	type Error = OsmError;
+
+	fn try_from(raw: RawOsmData) -> Result<Self, Self::Error> {
+		convert(raw, &mut Options::default(), Err)
+	}
+}
+
+/// Parses JSON data with [parse_str].
+impl std::str::FromStr for OsmData {
+	type Err = OsmError;
+
+	fn from_str(json: &str) -> Result<Self, Self::Err> {
+		parse_str(json)
+	}
+}
+
+/// Parses JSON data with [parse_str].
+impl TryFrom<&str> for OsmData {
+	type Error = OsmError;
+
+	fn try_from(json: &str) -> Result<Self, Self::Error> {
+		parse_str(json)
+	}
+}
+
+/// Parse JSON data like [parse_str], calling `on_progress` with the number of converted and total elements,
+/// e.g. to drive a progress bar. It's called before each element and once more when all are converted.
+/// Reading the JSON itself happens before the first call and isn't reported.
+pub fn parse_with_progress(json: &str, mut on_progress: impl FnMut(usize, usize)) -> Result<OsmData, OsmError> {
+	let raw = serde_json::from_str::<RawOsmData>(json)?;
+	convert(raw, &mut Options { on_progress: Some(&mut on_progress), ..Default::default() }, Err)
+}
+
+/// Parse JSON data like [parse_str], but fail on duplicate node, way or relation ids
+/// instead of letting the later element overwrite the earlier one.
+pub fn parse_strict(json: &str) -> Result<OsmData, OsmError> {
+	let raw = serde_json::from_str::<RawOsmData>(json)?;
+	convert(raw, &mut Options { strict: true, ..Default::default() }, Err)
+}
+
+/// Parse only the metadata and bounds, e.g. to decide whether a file is worth processing.
+/// The elements are skipped without being converted, so the returned data intentionally has none.
+/// Unlike [parse_str], missing bounds are [Bounds::ZERO] since there are no nodes to calculate them from.
+/// Unmodeled fields aren't kept even with `preserve-unknown`, that would require buffering the whole document.
+pub fn parse_header(json: &str) -> Result<OsmData, OsmError> {
+	let raw = serde_json::from_str::<RawHeader>(json)?;
+
 	Ok(OsmData {
 		version: raw.version,
 		generator: raw.generator,
 		copyright: raw.copyright,
 		attribution: raw.attribution,
 		license: raw.license,
-		bounds: raw.bounds.into(),
+		bounds: raw.bounds.map_or(Bounds::ZERO, Bounds::from),
+		..Default::default()
+	})
+}
+
+/// Parse JSON data like [parse_str], but only keep node and way tags whose key is in `keep`,
+/// to save memory when most tags are irrelevant. An empty `keep` drops all of them,
+/// elements left without tags have [None] tags. Relation tags are kept, since their `type` decides how members are used.
+pub fn parse_with_tag_filter(json: &str, keep: &HashSet<String>) -> Result<OsmData, OsmError> {
+	let raw = serde_json::from_str::<RawOsmData>(json)?;
+	convert(raw, &mut Options { tag_filter: Some(keep), ..Default::default() }, Err)
+}
+
+#[derive(Default)]
+struct Options<'a> {
+	/// Report duplicate ids as errors.
+	strict: bool,
+	/// Called with the number of converted and total elements, see [parse_with_progress].
+	on_progress: Option<&'a mut dyn FnMut(usize, usize)>,
+	/// Node and way tag keys to keep, see [parse_with_tag_filter].
+	tag_filter: Option<&'a HashSet<String>>,
+}
+
+/// Converts all elements, passing element errors to `on_error` which decides whether to abort.
+fn convert(
+	raw: RawOsmData,
+	options: &mut Options,
+	on_error: impl FnMut(OsmError) -> Result<(), OsmError>,
+) -> Result<OsmData, OsmError> {
+	let (nodes, ways, relations) = convert_elements(raw.elements.unwrap_or_default(), options, on_error)?;
+
+	Ok(OsmData {
+		version: raw.version,
+		generator: raw.generator,
+		copyright: raw.copyright,
+		attribution: raw.attribution,
+		license: raw.license,
+		bounds: raw.bounds.map_or_else(|| Bounds::calculate(&nodes), Bounds::from),
 		nodes,
 		ways,
+		relations,
+		#[cfg(feature = "preserve-unknown")]
+		extra: raw.extra,
 	})
 }
+
+/// A deserializable element, with access to its unmodeled fields.
+trait Element: DeserializeOwned {
+	/// Known fields the element drops, which must not end up in its extras.
+	/// JOSM marks edited elements with an `action`, which is meaningless once loaded.
+	#[cfg(feature = "preserve-unknown")]
+	const IGNORED: &'static [&'static str] = &["type", "action"];
+
+	#[cfg(feature = "preserve-unknown")]
+	fn extra_mut(&mut self) -> &mut Extra;
+}
+
+/// The metadata nodes and ways drop with the `minimal` feature.
+#[cfg(all(feature = "preserve-unknown", feature = "minimal"))]
+const METADATA: &[&str] = &["type", "action", "timestamp", "version", "changeset", "changesetId", "user", "uid"];
+
+impl Element for RawNode {
+	#[cfg(all(feature = "preserve-unknown", feature = "minimal"))]
+	const IGNORED: &'static [&'static str] = METADATA;
+
+	#[cfg(feature = "preserve-unknown")]
+	fn extra_mut(&mut self) -> &mut Extra {
+		&mut self.extra
+	}
+}
+
+impl Element for Way {
+	#[cfg(all(feature = "preserve-unknown", feature = "minimal"))]
+	const IGNORED: &'static [&'static str] = METADATA;
+
+	#[cfg(feature = "preserve-unknown")]
+	fn extra_mut(&mut self) -> &mut Extra {
+		&mut self.extra
+	}
+}
+
+impl Element for Relation {
+	#[cfg(feature = "preserve-unknown")]
+	fn extra_mut(&mut self) -> &mut Extra {
+		&mut self.extra
+	}
+}
+
+/// An element and the fields it doesn't model.
+#[cfg(feature = "preserve-unknown")]
+#[derive(serde::Deserialize)]
+struct WithExtra<T> {
+	#[serde(flatten)]
+	element: T,
+	#[serde(flatten)]
+	extra: Extra,
+}
+
+#[cfg(not(feature = "preserve-unknown"))]
+fn deserialize_element<T: Element>(e: serde_json::Value) -> serde_json::Result<T> {
+	serde_json::from_value(e)
+}
+
+/// Deserializes an element and keeps its unmodeled fields, apart from [Element::IGNORED].
+#[cfg(feature = "preserve-unknown")]
+fn deserialize_element<T: Element>(e: serde_json::Value) -> serde_json::Result<T> {
+	let WithExtra { mut element, mut extra } = serde_json::from_value::<WithExtra<T>>(e)?;
+	extra.retain(|k, _| !T::IGNORED.contains(&k.as_str()));
+	*element.extra_mut() = extra;
+	Ok(element)
+}
+
+/// Drops the tags of a JSON element that aren't in `keep` before they are converted,
+/// and the whole `tags` object if none are left.
+fn filter_tags(e: &mut serde_json::Value, keep: &HashSet<String>) {
+	let Some(tags) = e.get_mut("tags").and_then(serde_json::Value::as_object_mut) else { return };
+	tags.retain(|k, _| keep.contains(k));
+	if tags.is_empty() {
+		if let Some(e) = e.as_object_mut() {
+			e.remove("tags");
+		}
+	}
+}
+
+/// Converts a list of JSON elements into the maps of each element type.
+fn convert_elements(
+	elements: Vec<serde_json::Value>,
+	options: &mut Options,
+	mut on_error: impl FnMut(OsmError) -> Result<(), OsmError>,
+) -> Result<(Nodes, Ways, Relations), OsmError> {
+	let mut nodes = Nodes::new();
+	let mut ways = Ways::new();
+	let mut relations = Relations::new();
+
+	let total = elements.len();
+	for (index, mut e) in elements.into_iter().enumerate() {
+		if let Some(on_progress) = &mut options.on_progress {
+			on_progress(index, total);
+		}
+
+		if let Some(keep) = options.tag_filter {
+			if matches!(e["type"].as_str(), Some("node" | "way")) {
+				filter_tags(&mut e, keep);
+			}
+		}
+
+		let element_err = |kind| OsmError::Element { index, kind };
+
+		let Some(t) = e["type"].as_str() else {
+			on_error(element_err(ElementError::MissingType))?;
+			continue;
+		};
+
+		match t {
+			"node" => match deserialize_element::<RawNode>(e) {
+				Ok(node) if options.strict && nodes.contains_key(&node.id) => {
+					on_error(element_err(ElementError::DuplicateId(MemberType::Node, node.id)))?;
+				}
+				Ok(node) => { nodes.insert(node.id, node.into()); }
+				Err(e) => on_error(element_err(ElementError::Invalid(e)))?,
+			}
+			"way" => match deserialize_element::<Way>(e) {
+				Ok(way) if options.strict && ways.contains_key(&way.id) => {
+					on_error(element_err(ElementError::DuplicateId(MemberType::Way, way.id)))?;
+				}
+				Ok(way) => { ways.insert(way.id, way); }
+				Err(e) => on_error(element_err(ElementError::Invalid(e)))?,
+			}
+			"relation" => match deserialize_element::<Relation>(e) {
+				Ok(relation) if options.strict && relations.contains_key(&relation.id) => {
+					on_error(element_err(ElementError::DuplicateId(MemberType::Relation, relation.id)))?;
+				}
+				Ok(relation) => { relations.insert(relation.id, relation); }
+				Err(e) => on_error(element_err(ElementError::Invalid(e)))?,
+			}
+			t => on_error(element_err(ElementError::UnknownType(t.to_string())))?,
+		}
+	}
+
+	if let Some(on_progress) = &mut options.on_progress {
+		on_progress(total, total);
+	}
+
+	Ok((nodes, ways, relations))
+}
+
+/// Converts JSON elements like [parse_str], failing on the first invalid element.
+pub(crate) fn parse_elements(elements: Vec<serde_json::Value>) -> Result<(Nodes, Ways, Relations), OsmError> {
+	convert_elements(elements, &mut Options::default(), Err)
+}
+
+#[cfg(test)]
+pub(crate) mod tests_parser {
+	use super::*;
+
+	/// Four nodes around a crossing, two ways and a route relation.
+	pub(crate) const SAMPLE: &str = r#"{
+		"version": "0.6",
+		"generator": "CGImap 0.9.3 (2403633 spike-08.openstreetmap.org)",
+		"copyright": "OpenStreetMap and contributors",
+		"attribution": "http://www.openstreetmap.org/copyright",
+		"license": "http://opendatacommons.org/licenses/odbl/1-0/",
+		"bounds": {"minlat": 41.30365, "minlon": -81.90212, "maxlat": 41.30453, "maxlon": -81.90126},
+		"elements": [
+			{"type": "node", "id": 1, "lat": 41.30365, "lon": -81.90171, "timestamp": "2023-01-01T00:00:00Z", "version": 1, "changeset": 100, "user": "alice", "uid": 11},
+			{"type": "node", "id": 2, "lat": 41.30453, "lon": -81.90169, "timestamp": "2023-01-01T00:00:00Z", "version": 2, "changeset": 100, "user": "alice", "uid": 11, "tags": {"amenity": "cafe", "name": "Corner Cafe"}},
+			{"type": "node", "id": 3, "lat": 41.30407, "lon": -81.90212, "timestamp": "2023-02-01T00:00:00Z", "version": 1, "changeset": 101, "user": "bob", "uid": 12},
+			{"type": "node", "id": 4, "lat": 41.30407, "lon": -81.90126, "timestamp": "2023-02-01T00:00:00Z", "version": 1, "changeset": 101, "user": "bob", "uid": 12},
+			{"type": "way", "id": 10, "timestamp": "2023-01-01T00:00:00Z", "version": 1, "changeset": 100, "user": "alice", "uid": 11, "nodes": [1, 2], "tags": {"highway": "residential", "name": "Main Street"}},
+			{"type": "way", "id": 11, "timestamp": "2023-02-01T00:00:00Z", "version": 3, "changeset": 101, "user": "bob", "uid": 12, "nodes": [3, 4], "tags": {"highway": "footway"}},
+			{"type": "relation", "id": 20, "timestamp": "2023-02-01T00:00:00Z", "version": 1, "changeset": 101, "user": "bob", "uid": 12, "members": [{"type": "way", "ref": 10, "role": ""}], "tags": {"type": "route"}}
+		]
+	}"#;
+
+	#[test]
+	fn sample() {
+		let data = parse_str(SAMPLE).unwrap();
+
+		assert_eq!(data.nodes.len(), 4);
+		assert_eq!(data.ways.len(), 2);
+		assert_eq!(data.ways[&10].nodes, vec![1, 2]);
+		assert_eq!(data.nodes[&2].tags.as_ref().unwrap()["amenity"], "cafe");
+		assert_eq!(data.relations[&20].members[0], Member { member_type: MemberType::Way, id: 10, role: String::new() });
+	}
+
+	#[test]
+	fn from_str() {
+		let data: OsmData = SAMPLE.parse().unwrap();
+		assert_eq!(data, parse_str(SAMPLE).unwrap());
+		assert_eq!(OsmData::try_from(SAMPLE).unwrap(), data);
+		assert!("{}".parse::<OsmData>().unwrap_err().is_conversion());
+	}
+
+	#[test]
+	fn progress() {
+		let mut calls = Vec::new();
+		let data = parse_with_progress(SAMPLE, |done, total| calls.push((done, total))).unwrap();
+
+		assert_eq!(data, parse_str(SAMPLE).unwrap());
+		assert_eq!(calls, (0..=7).map(|i| (i, 7)).collect::<Vec<_>>());
+	}
+
+	#[test]
+	#[cfg(not(feature = "minimal"))]
+	fn uid() {
+		let data = parse_str(SAMPLE).unwrap();
+		assert_eq!(data.nodes[&3].uid, 12);
+		assert_eq!(data.ways[&10].uid, 11);
+
+		// redacted elements have neither user nor uid
+		let json = SAMPLE.replace(r#""user": "bob", "uid": 12, "nodes""#, r#""nodes""#);
+		let data = parse_str(&json).unwrap();
+		assert_eq!(data.ways[&11].user, "");
+		assert_eq!(data.ways[&11].uid, 0);
+	}
+
+	#[test]
+	fn empty() {
+		assert!(!parse_str(SAMPLE).unwrap().is_empty());
+
+		let header = r#""version": "0.6", "generator": "", "copyright": "", "attribution": "", "license": "",
+			"bounds": {"minlat": 0, "minlon": 0, "maxlat": 0, "maxlon": 0}"#;
+
+		for json in [format!("{{{header}, \"elements\": null}}"), format!("{{{header}}}")] {
+			let data = parse_str(&json).unwrap();
+			assert!(data.is_empty());
+			assert_eq!(data.version, "0.6");
+		}
+	}
+
+	#[test]
+	fn header() {
+		let data = parse_header(SAMPLE).unwrap();
+		let full = parse_str(SAMPLE).unwrap();
+
+		assert!(data.is_empty());
+		assert_eq!(data.generator, full.generator);
+		assert_eq!(data.license, full.license);
+		assert_eq!(data.bounds, full.bounds);
+		// elements aren't converted, so invalid ones don't matter
+		assert!(parse_header(r#"{"version": "", "generator": "", "copyright": "", "attribution": "", "license": "", "elements": [1]}"#).is_ok());
+	}
+
+	#[test]
+	fn josm() {
+		let json = r#"{
+			"version": "0.6", "generator": "JOSM",
+			"elements": [
+				{"type": "node", "id": 1, "lat": 1.5, "lon": 2.0, "version": 3, "changeset": 7, "timestamp": "2023-01-01T00:00:00Z", "user": "alice", "uid": 11},
+				{"type": "node", "id": 2, "lat": 1.0, "lon": 3.0, "action": "modify", "version": 1, "changeset": 7, "timestamp": "2023-01-01T00:00:00Z"},
+				{"type": "node", "id": 3, "lat": 1.2, "lon": 2.5, "action": "modify"},
+				{"type": "way", "id": 4, "action": "modify", "nodes": [1, 2, 3], "tags": {"highway": "path"}},
+				{"type": "relation", "id": 5, "action": "modify", "members": [{"type": "way", "ref": 4, "role": ""}]}
+			]
+		}"#;
+		let data = parse_str(json).unwrap();
+
+		assert_eq!(data.generator, "JOSM");
+		assert_eq!(data.license, "");
+		assert_eq!(data.nodes[&3], Node { id: 3, pos: Coordinate::new(1.2, 2.5), ..Default::default() });
+		assert_eq!(data.ways[&4], Way { id: 4, nodes: vec![1, 2, 3], tags: Some(Tags::from([("highway".to_string(), "path".to_string())])), ..Default::default() });
+		assert_eq!(data.relations[&5].members.len(), 1);
+		#[cfg(feature = "preserve-unknown")]
+		assert!(data.nodes[&2].extra.is_empty());
+	}
+
+	#[test]
+	fn missing_bounds() {
+		let json = r#"{
+			"version": "0.6", "generator": "", "copyright": "", "attribution": "", "license": "",
+			"elements": [
+				{"type": "node", "id": 1, "lat": 1.5, "lon": 2.0, "timestamp": "", "version": 1, "changeset": 1, "user": ""},
+				{"type": "node", "id": 2, "lat": 1.0, "lon": 3.0, "timestamp": "", "version": 1, "changeset": 1, "user": ""},
+				{"type": "way", "id": 1, "timestamp": "", "version": 1, "changeset": 1, "user": "", "nodes": [1, 2]}
+			]
+		}"#;
+		let data = parse_str(json).unwrap();
+
+		assert_eq!(data.bounds, Bounds::new(Coordinate::new(1., 2.), Coordinate::new(1.5, 3.)));
+		assert_eq!(data.ways[&1].nodes, vec![1, 2]);
+	}
+
+	#[test]
+	fn camel_case() {
+		let json = SAMPLE
+			.replace(r#""minlat""#, r#""minLat""#)
+			.replace(r#""maxlon""#, r#""maxLon""#)
+			.replace(r#""changeset""#, r#""changesetId""#);
+		assert!(json.contains("minLat") && json.contains("changesetId"));
+
+		assert_eq!(parse_str(&json).unwrap(), parse_str(SAMPLE).unwrap());
+	}
+
+	#[test]
+	fn node_map() {
+		let mut nodes: Nodes = NodeMap::new();
+		nodes.insert(1, Node::default());
+		let ways: Ways = NodeMap::with_capacity(8);
+
+		assert_eq!(nodes.len(), 1);
+		assert!(ways.capacity() >= 8);
+	}
+
+	#[cfg(feature = "async")]
+	#[tokio::test]
+	async fn parse_async() {
+		assert_eq!(super::parse_async(SAMPLE.as_bytes()).await.unwrap(), parse_str(SAMPLE).unwrap());
+		assert!(super::parse_async(&SAMPLE.as_bytes()[..100]).await.unwrap_err().is_syntax());
+	}
+
+	#[cfg(feature = "memmap")]
+	#[test]
+	fn parse_mmap() {
+		let path = std::env::temp_dir().join(format!("osm-parser-mmap-{}.json", std::process::id()));
+		std::fs::write(&path, SAMPLE).unwrap();
+		let parsed = super::parse_mmap(&path);
+		std::fs::remove_file(&path).unwrap();
+
+		assert_eq!(parsed.unwrap(), parse_str(SAMPLE).unwrap());
+		assert!(matches!(super::parse_mmap(&path), Err(OsmError::Io(_))));
+	}
+
+	#[cfg(feature = "gzip")]
+	#[test]
+	fn parse_auto() {
+		use std::io::{Read, Write};
+
+		/// Returns a single byte per read to test the peeking.
+		struct Trickle<'a>(&'a [u8]);
+
+		impl Read for Trickle<'_> {
+			fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+				let n = buf.len().min(self.0.len()).min(1);
+				buf[..n].copy_from_slice(&self.0[..n]);
+				self.0 = &self.0[n..];
+				Ok(n)
+			}
+		}
+
+		let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+		encoder.write_all(SAMPLE.as_bytes()).unwrap();
+		let gz = encoder.finish().unwrap();
+		let expected = parse_str(SAMPLE).unwrap();
+
+		assert_eq!(super::parse_auto(gz.as_slice()).unwrap(), expected);
+		assert_eq!(super::parse_auto(Trickle(&gz)).unwrap(), expected);
+		assert_eq!(super::parse_auto(SAMPLE.as_bytes()).unwrap(), expected);
+		assert_eq!(super::parse_auto(Trickle(SAMPLE.as_bytes())).unwrap(), expected);
+		assert!(matches!(super::parse_auto(&[0x1f][..]), Err(OsmError::Json(_))));
+	}
+
+	#[test]
+	fn json_error_position() {
+		let err = parse_str("{\n\t\"version\": \"0.6\",\n\t\"generator\": ]\n}").unwrap_err();
+
+		assert!(matches!(err, OsmError::Json(_)));
+		assert_eq!(err.to_string(), "JSON error at 3:15: expected value");
+	}
+
+	#[test]
+	fn error_classification() {
+		let truncated = parse_str(&SAMPLE[..100]).unwrap_err();
+		assert!(truncated.is_syntax() && !truncated.is_conversion());
+
+		let missing_field = parse_str(r#"{"version": "0.6"}"#).unwrap_err();
+		assert!(missing_field.is_conversion() && !missing_field.is_syntax());
+
+		let element = parse_str(&SAMPLE.replace(r#""type": "way", "id": 11"#, r#""type": 1, "id": 11"#)).unwrap_err();
+		assert!(element.is_conversion() && !element.is_syntax());
+	}
+
+	#[test]
+	fn element_error_index() {
+		let json = SAMPLE.replace(r#""type": "way", "id": 11"#, r#""type": "area", "id": 11"#);
+		let err = parse_str(&json).unwrap_err();
+
+		assert!(matches!(err, OsmError::Element { index: 5, kind: ElementError::UnknownType(_) }));
+		assert_eq!(err.to_string(), "element 5: invalid type \"area\"");
+	}
+
+	#[test]
+	fn lossy() {
+		let (data, errors) = parse_lossy(SAMPLE);
+		assert_eq!(data, parse_str(SAMPLE).unwrap());
+		assert!(errors.is_empty());
+
+		let json = SAMPLE.replace(r#""id": 3, "lat": 41.30407"#, r#""id": 3, "lat": "north""#);
+		let (data, errors) = parse_lossy(&json);
+		assert_eq!(data.nodes.len(), 3);
+		assert_eq!(data.ways.len(), 2);
+		assert!(matches!(errors[..], [OsmError::Element { index: 2, kind: ElementError::Invalid(_) }]));
+	}
+
+	#[test]
+	fn tag_filter() {
+		let keep = HashSet::from(["amenity".to_string(), "type".to_string()]);
+		let data = parse_with_tag_filter(SAMPLE, &keep).unwrap();
+
+		assert_eq!(data.nodes[&2].tags, Some(Tags::from([("amenity".to_string(), "cafe".to_string())])));
+		assert_eq!(data.ways[&10].tags, None);
+		assert_eq!(data.relations[&20].tags, parse_str(SAMPLE).unwrap().relations[&20].tags);
+
+		let data = parse_with_tag_filter(SAMPLE, &HashSet::new()).unwrap();
+		assert!(data.nodes.values().all(|n| n.tags.is_none()) && data.ways.values().all(|w| w.tags.is_none()));
+	}
+
+	#[test]
+	fn strict() {
+		assert_eq!(parse_strict(SAMPLE).unwrap(), parse_str(SAMPLE).unwrap());
+
+		let json = SAMPLE.replace(r#""type": "node", "id": 4"#, r#""type": "node", "id": 1"#);
+		let err = parse_strict(&json).unwrap_err();
+
+		assert!(matches!(err, OsmError::Element { index: 3, kind: ElementError::DuplicateId(MemberType::Node, 1) }));
+		assert_eq!(err.to_string(), "element 3: duplicate node id 1");
+		assert_eq!(parse_str(&json).unwrap().nodes.len(), 3);
+	}
+}
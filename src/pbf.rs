@@ -0,0 +1,823 @@
+//! Decoder for the OSM PBF format, see https://wiki.openstreetmap.org/wiki/PBF_Format.
+//! Nodes, dense nodes, ways and relations are decoded, changesets are skipped.
+
+use std::io::Read;
+
+use flate2::read::ZlibDecoder;
+
+use crate::{narrow, Bounds, Coordinate, Member, MemberType, Node, Nodes, OsmData, OsmError, Relation, Tags, Way};
+use crate::parser::Id;
+
+/// Features this decoder understands, files requiring anything else are rejected.
+const SUPPORTED_FEATURES: [&str; 2] = ["OsmSchema-V0.6", "DenseNodes"];
+
+/// Maximum sizes from the specification, guarding against allocating garbage lengths.
+const MAX_HEADER_SIZE: usize = 64 * 1024;
+const MAX_BLOB_SIZE: usize = 32 * 1024 * 1024;
+
+type Result<T> = std::result::Result<T, OsmError>;
+
+fn err<T>(message: impl Into<String>) -> Result<T> {
+	Err(OsmError::Pbf(message.into()))
+}
+
+fn unsupported<T>(message: impl Into<String>) -> Result<T> {
+	Err(OsmError::Unsupported(message.into()))
+}
+
+/// Parse an OSM PBF file from a reader, e.g. a planet extract.
+pub fn parse_pbf<R: Read>(mut reader: R) -> Result<OsmData> {
+	let mut data = OsmData {
+		version: "0.6".to_string(),
+		..Default::default()
+	};
+	let mut bounds = None;
+
+	while let Some((kind, blob)) = read_blob(&mut reader)? {
+		match kind.as_str() {
+			"OSMHeader" => bounds = decode_header(&blob, &mut data)?,
+			"OSMData" => decode_primitive_block(&blob, &mut data)?,
+			_ => {} // unknown blob types are skipped as recommended by the specification
+		}
+	}
+
+	data.bounds = bounds.unwrap_or_else(|| Bounds::calculate(&data.nodes));
+	Ok(data)
+}
+
+//region Protobuf
+/// Field values as encoded on the wire.
+enum Value<'a> {
+	Varint(u64),
+	Bytes(&'a [u8]),
+	Fixed,
+}
+
+impl<'a> Value<'a> {
+	fn varint(&self) -> Result<u64> {
+		match self {
+			Value::Varint(v) => Ok(*v),
+			_ => err("expected varint field"),
+		}
+	}
+
+	fn sint(&self) -> Result<i64> {
+		self.varint().map(zigzag)
+	}
+
+	fn bytes(&self) -> Result<&'a [u8]> {
+		match self {
+			Value::Bytes(b) => Ok(b),
+			_ => err("expected length-delimited field"),
+		}
+	}
+
+	fn string(&self) -> Result<String> {
+		String::from_utf8(self.bytes()?.to_vec()).or_else(|_| err("invalid UTF-8 string"))
+	}
+
+	/// Packed repeated varints.
+	fn packed(&self) -> Result<Vec<u64>> {
+		let mut reader = Message::new(self.bytes()?);
+		let mut values = Vec::new();
+		while !reader.is_empty() {
+			values.push(reader.varint()?);
+		}
+		Ok(values)
+	}
+
+	/// Packed repeated delta-coded signed varints, decoded to absolute values.
+	fn packed_delta(&self) -> Result<Vec<i64>> {
+		let mut acc = 0i64;
+		Ok(self.packed()?.into_iter()
+			.map(|v| {
+				acc = acc.wrapping_add(zigzag(v));
+				acc
+			})
+			.collect())
+	}
+}
+
+fn zigzag(v: u64) -> i64 {
+	((v >> 1) as i64) ^ -((v & 1) as i64)
+}
+
+/// Sequential reader over the fields of an encoded message.
+struct Message<'a> {
+	buf: &'a [u8],
+}
+
+impl<'a> Message<'a> {
+	fn new(buf: &'a [u8]) -> Self {
+		Self { buf }
+	}
+
+	fn is_empty(&self) -> bool {
+		self.buf.is_empty()
+	}
+
+	fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+		if len > self.buf.len() {
+			return err("unexpected end of message");
+		}
+		let (head, tail) = self.buf.split_at(len);
+		self.buf = tail;
+		Ok(head)
+	}
+
+	fn varint(&mut self) -> Result<u64> {
+		let mut value = 0u64;
+		for shift in (0..64).step_by(7) {
+			let byte = self.take(1)?[0];
+			value |= u64::from(byte & 0x7f) << shift;
+			if byte & 0x80 == 0 {
+				return Ok(value);
+			}
+		}
+		err("varint too long")
+	}
+
+	fn next_field(&mut self) -> Result<Option<(u64, Value<'a>)>> {
+		if self.is_empty() {
+			return Ok(None);
+		}
+
+		let key = self.varint()?;
+		let value = match key & 7 {
+			0 => Value::Varint(self.varint()?),
+			1 => { self.take(8)?; Value::Fixed }
+			2 => {
+				let len = self.varint()? as usize;
+				Value::Bytes(self.take(len)?)
+			}
+			5 => { self.take(4)?; Value::Fixed }
+			t => return err(format!("unsupported wire type {t}")),
+		};
+		Ok(Some((key >> 3, value)))
+	}
+}
+//endregion
+
+//region Blobs
+/// Reads the next `BlobHeader` and `Blob`, returning the blob type and decompressed contents.
+fn read_blob<R: Read>(reader: &mut R) -> Result<Option<(String, Vec<u8>)>> {
+	let mut len = [0u8; 4];
+	match reader.read_exact(&mut len) {
+		Ok(()) => {}
+		Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+		Err(e) => return Err(e.into()),
+	}
+
+	let len = u32::from_be_bytes(len) as usize;
+	if len > MAX_HEADER_SIZE {
+		return err("blob header too large");
+	}
+	let mut header = vec![0; len];
+	reader.read_exact(&mut header)?;
+
+	let mut kind = None;
+	let mut data_size = None;
+	let mut message = Message::new(&header);
+	while let Some((field, value)) = message.next_field()? {
+		match field {
+			1 => kind = Some(value.string()?),
+			3 => data_size = Some(value.varint()? as usize),
+			_ => {}
+		}
+	}
+
+	let (Some(kind), Some(data_size)) = (kind, data_size) else {
+		return err("blob header is missing its type or size");
+	};
+	if data_size > MAX_BLOB_SIZE {
+		return err("blob too large");
+	}
+	let mut blob = vec![0; data_size];
+	reader.read_exact(&mut blob)?;
+
+	Ok(Some((kind, decompress(&blob)?)))
+}
+
+fn decompress(blob: &[u8]) -> Result<Vec<u8>> {
+	let mut raw_size = 0;
+	let mut message = Message::new(blob);
+	while let Some((field, value)) = message.next_field()? {
+		match field {
+			1 => return Ok(value.bytes()?.to_vec()),
+			2 => raw_size = value.varint()? as usize,
+			3 => {
+				// the declared size bounds the output, so crafted blobs can't inflate without limit
+				let limit = raw_size.min(MAX_BLOB_SIZE);
+				let mut data = Vec::with_capacity(limit);
+				ZlibDecoder::new(value.bytes()?).take(limit as u64 + 1).read_to_end(&mut data)?;
+				if data.len() != raw_size {
+					return err("decompressed blob doesn't match its declared size");
+				}
+				return Ok(data);
+			}
+			4..=7 => return unsupported("only raw and zlib compressed blobs are supported"),
+			_ => {}
+		}
+	}
+	err("blob has no data")
+}
+//endregion
+
+//region Blocks
+/// Decodes a `HeaderBlock` into the metadata, returning its bounding box if present.
+fn decode_header(blob: &[u8], data: &mut OsmData) -> Result<Option<Bounds>> {
+	let mut bounds = None;
+	let mut message = Message::new(blob);
+
+	while let Some((field, value)) = message.next_field()? {
+		match field {
+			1 => {
+				let (mut left, mut right, mut top, mut bottom) = (0, 0, 0, 0);
+				let mut bbox = Message::new(value.bytes()?);
+				while let Some((field, value)) = bbox.next_field()? {
+					match field {
+						1 => left = value.sint()?,
+						2 => right = value.sint()?,
+						3 => top = value.sint()?,
+						4 => bottom = value.sint()?,
+						_ => {}
+					}
+				}
+
+				let deg = |nano: i64| narrow(nano as f64 * 1e-9);
+				bounds = Some(Bounds::new(
+					Coordinate::new(deg(bottom), deg(left)),
+					Coordinate::new(deg(top), deg(right)),
+				));
+			}
+			4 => {
+				let feature = value.string()?;
+				if !SUPPORTED_FEATURES.contains(&feature.as_str()) {
+					return unsupported(format!("unsupported required feature \"{feature}\""));
+				}
+			}
+			16 => data.generator = value.string()?,
+			_ => {}
+		}
+	}
+
+	Ok(bounds)
+}
+
+/// Block-wide parameters for decoding coordinates, timestamps and strings.
+struct Block {
+	strings: Vec<String>,
+	granularity: i64,
+	lat_offset: i64,
+	lon_offset: i64,
+	date_granularity: i64,
+}
+
+impl Block {
+	fn coordinate(&self, lat: i64, lon: i64) -> Result<Coordinate> {
+		let deg = |offset: i64, value: i64| match self.granularity.checked_mul(value).and_then(|v| v.checked_add(offset)) {
+			Some(nano) => Ok(narrow(nano as f64 * 1e-9)),
+			None => err("coordinate out of range"),
+		};
+		Ok(Coordinate::new(deg(self.lat_offset, lat)?, deg(self.lon_offset, lon)?))
+	}
+
+	fn string(&self, index: u64) -> Result<&str> {
+		match self.strings.get(index as usize) {
+			Some(s) => Ok(s),
+			None => err(format!("string index {index} out of range")),
+		}
+	}
+
+	fn timestamp(&self, timestamp: i64) -> Result<String> {
+		match timestamp.checked_mul(self.date_granularity) {
+			Some(millis) => Ok(format_timestamp(millis / 1000)),
+			None => err("timestamp out of range"),
+		}
+	}
+
+	fn tags(&self, keys: &[u64], vals: &[u64]) -> Result<Option<Tags>> {
+		if keys.is_empty() {
+			return Ok(None);
+		}
+		if keys.len() != vals.len() {
+			return err("mismatched tag keys and values");
+		}
+
+		keys.iter().zip(vals)
+			.map(|(k, v)| Ok((self.string(*k)?.to_string(), self.string(*v)?.to_string())))
+			.collect::<Result<Tags>>()
+			.map(Some)
+	}
+}
+
+/// Metadata shared by all elements.
+struct Info {
+	version: u32,
+	timestamp: String,
+	changeset: u64,
+	uid: u64,
+	user: String,
+	visible: bool,
+}
+
+impl Default for Info {
+	fn default() -> Self {
+		Self { version: 0, timestamp: String::new(), changeset: 0, uid: 0, user: String::new(), visible: true }
+	}
+}
+
+impl Info {
+	/// Copies the metadata onto a node, only `visible` with the `minimal` feature.
+	fn apply_to_node(self, node: &mut Node) {
+		#[cfg(not(feature = "minimal"))]
+		{
+			node.timestamp = self.timestamp;
+			node.version = self.version;
+			node.changeset = self.changeset;
+			node.user = self.user;
+			node.uid = self.uid;
+		}
+		node.visible = self.visible;
+	}
+
+	/// Copies the metadata onto a way, only `visible` with the `minimal` feature.
+	fn apply_to_way(self, way: &mut Way) {
+		#[cfg(not(feature = "minimal"))]
+		{
+			way.timestamp = self.timestamp;
+			way.version = self.version;
+			way.changeset = self.changeset;
+			way.user = self.user;
+			way.uid = self.uid;
+		}
+		way.visible = self.visible;
+	}
+
+	/// Copies the metadata onto a relation, which keeps it even with the `minimal` feature.
+	fn apply_to_relation(self, relation: &mut Relation) {
+		relation.timestamp = self.timestamp;
+		relation.version = self.version;
+		relation.changeset = self.changeset;
+		relation.user = self.user;
+		relation.uid = self.uid;
+		relation.visible = self.visible;
+	}
+}
+
+fn decode_info(buf: &[u8], block: &Block) -> Result<Info> {
+	let mut info = Info::default();
+	let mut message = Message::new(buf);
+
+	while let Some((field, value)) = message.next_field()? {
+		match field {
+			1 => info.version = value.varint()? as u32,
+			2 => info.timestamp = block.timestamp(value.varint()? as i64)?,
+			3 => info.changeset = value.varint()?,
+			4 => info.uid = value.varint()?,
+			5 => info.user = block.string(value.varint()?)?.to_string(),
+			6 => info.visible = value.varint()? != 0,
+			_ => {}
+		}
+	}
+
+	Ok(info)
+}
+
+fn decode_primitive_block(blob: &[u8], data: &mut OsmData) -> Result<()> {
+	let mut block = Block {
+		strings: Vec::new(),
+		granularity: 100,
+		lat_offset: 0,
+		lon_offset: 0,
+		date_granularity: 1000,
+	};
+
+	// The parameters may follow the groups, so collect the groups and decode them afterwards.
+	let mut groups = Vec::new();
+	let mut message = Message::new(blob);
+	while let Some((field, value)) = message.next_field()? {
+		match field {
+			1 => {
+				let mut table = Message::new(value.bytes()?);
+				while let Some((field, value)) = table.next_field()? {
+					if field == 1 {
+						block.strings.push(String::from_utf8_lossy(value.bytes()?).into_owned());
+					}
+				}
+			}
+			2 => groups.push(value.bytes()?),
+			17 => block.granularity = value.varint()? as i64,
+			18 => block.date_granularity = value.varint()? as i64,
+			19 => block.lat_offset = value.varint()? as i64,
+			20 => block.lon_offset = value.varint()? as i64,
+			_ => {}
+		}
+	}
+
+	for group in groups {
+		let mut message = Message::new(group);
+		while let Some((field, value)) = message.next_field()? {
+			match field {
+				1 => {
+					let node = decode_node(value.bytes()?, &block)?;
+					data.nodes.insert(node.id, node);
+				}
+				2 => decode_dense(value.bytes()?, &block, &mut data.nodes)?,
+				3 => {
+					let way = decode_way(value.bytes()?, &block)?;
+					data.ways.insert(way.id, way);
+				}
+				4 => {
+					let relation = decode_relation(value.bytes()?, &block)?;
+					data.relations.insert(relation.id, relation);
+				}
+				_ => {} // changesets are not supported
+			}
+		}
+	}
+
+	Ok(())
+}
+
+fn decode_node(buf: &[u8], block: &Block) -> Result<Node> {
+	let (mut id, mut lat, mut lon) = (0, 0, 0);
+	let (mut keys, mut vals) = (Vec::new(), Vec::new());
+	let mut info = Info::default();
+
+	let mut message = Message::new(buf);
+	while let Some((field, value)) = message.next_field()? {
+		match field {
+			1 => id = value.sint()?,
+			2 => keys = value.packed()?,
+			3 => vals = value.packed()?,
+			4 => info = decode_info(value.bytes()?, block)?,
+			8 => lat = value.sint()?,
+			9 => lon = value.sint()?,
+			_ => {}
+		}
+	}
+
+	let mut node = Node {
+		id: id as Id,
+		pos: block.coordinate(lat, lon)?,
+		tags: block.tags(&keys, &vals)?,
+		..Default::default()
+	};
+	info.apply_to_node(&mut node);
+	Ok(node)
+}
+
+fn decode_dense(buf: &[u8], block: &Block, nodes: &mut Nodes) -> Result<()> {
+	let (mut ids, mut lats, mut lons) = (Vec::new(), Vec::new(), Vec::new());
+	let mut keys_vals = Vec::new();
+	let (mut versions, mut timestamps, mut changesets) = (Vec::new(), Vec::new(), Vec::new());
+	let (mut uids, mut users, mut visible) = (Vec::new(), Vec::new(), Vec::new());
+
+	let mut message = Message::new(buf);
+	while let Some((field, value)) = message.next_field()? {
+		match field {
+			1 => ids = value.packed_delta()?,
+			5 => {
+				let mut info = Message::new(value.bytes()?);
+				while let Some((field, value)) = info.next_field()? {
+					match field {
+						1 => versions = value.packed()?,
+						2 => timestamps = value.packed_delta()?,
+						3 => changesets = value.packed_delta()?,
+						4 => uids = value.packed_delta()?,
+						5 => users = value.packed_delta()?,
+						6 => visible = value.packed()?,
+						_ => {}
+					}
+				}
+			}
+			8 => lats = value.packed_delta()?,
+			9 => lons = value.packed_delta()?,
+			10 => keys_vals = value.packed()?,
+			_ => {}
+		}
+	}
+
+	if lats.len() != ids.len() || lons.len() != ids.len() {
+		return err("mismatched dense node arrays");
+	}
+
+	// Tags of all nodes are stored as `key, value, ..., 0` sequences in a single array.
+	let mut keys_vals = keys_vals.into_iter();
+
+	for (i, id) in ids.iter().enumerate() {
+		let (mut keys, mut vals) = (Vec::new(), Vec::new());
+		while let Some(key) = keys_vals.next().filter(|k| *k != 0) {
+			keys.push(key);
+			vals.push(keys_vals.next().map_or_else(|| err("dangling dense tag key"), Ok)?);
+		}
+
+		let info = Info {
+			timestamp: timestamps.get(i).map(|t| block.timestamp(*t)).transpose()?.unwrap_or_default(),
+			version: versions.get(i).copied().unwrap_or_default() as u32,
+			changeset: changesets.get(i).copied().unwrap_or_default() as u64,
+			user: match users.get(i) {
+				Some(sid) => block.string(*sid as u64)?.to_string(),
+				None => String::new(),
+			},
+			uid: uids.get(i).copied().unwrap_or_default() as u64,
+			visible: visible.get(i).is_none_or(|v| *v != 0),
+		};
+		let mut node = Node {
+			id: *id as Id,
+			pos: block.coordinate(lats[i], lons[i])?,
+			tags: block.tags(&keys, &vals)?,
+			..Default::default()
+		};
+		info.apply_to_node(&mut node);
+		nodes.insert(node.id, node);
+	}
+
+	Ok(())
+}
+
+fn decode_way(buf: &[u8], block: &Block) -> Result<Way> {
+	let mut way = Way::default();
+	let (mut keys, mut vals) = (Vec::new(), Vec::new());
+
+	let mut message = Message::new(buf);
+	while let Some((field, value)) = message.next_field()? {
+		match field {
+			1 => way.id = value.varint()?,
+			2 => keys = value.packed()?,
+			3 => vals = value.packed()?,
+			4 => decode_info(value.bytes()?, block)?.apply_to_way(&mut way),
+			8 => way.nodes = value.packed_delta()?.into_iter().map(|id| id as Id).collect(),
+			_ => {}
+		}
+	}
+
+	way.tags = block.tags(&keys, &vals)?;
+	Ok(way)
+}
+
+fn decode_relation(buf: &[u8], block: &Block) -> Result<Relation> {
+	let mut relation = Relation::default();
+	let (mut keys, mut vals) = (Vec::new(), Vec::new());
+	let (mut roles, mut ids, mut types) = (Vec::new(), Vec::new(), Vec::new());
+
+	let mut message = Message::new(buf);
+	while let Some((field, value)) = message.next_field()? {
+		match field {
+			1 => relation.id = value.varint()?,
+			2 => keys = value.packed()?,
+			3 => vals = value.packed()?,
+			4 => decode_info(value.bytes()?, block)?.apply_to_relation(&mut relation),
+			8 => roles = value.packed()?,
+			9 => ids = value.packed_delta()?,
+			10 => types = value.packed()?,
+			_ => {}
+		}
+	}
+
+	if roles.len() != ids.len() || types.len() != ids.len() {
+		return err("mismatched relation member arrays");
+	}
+
+	relation.members = ids.iter().zip(roles).zip(types)
+		.map(|((id, role), t)| Ok(Member {
+			member_type: match t {
+				0 => MemberType::Node,
+				1 => MemberType::Way,
+				2 => MemberType::Relation,
+				t => return err(format!("unknown member type {t}")),
+			},
+			id: *id as Id,
+			role: block.string(role)?.to_string(),
+		}))
+		.collect::<Result<_>>()?;
+	relation.tags = block.tags(&keys, &vals)?;
+	Ok(relation)
+}
+
+/// Formats seconds since the unix epoch as an ISO 8601 UTC timestamp, like the JSON API does.
+fn format_timestamp(seconds: i64) -> String {
+	let (days, time) = (seconds.div_euclid(86400), seconds.rem_euclid(86400));
+
+	// Civil date from days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html
+	let z = days + 719468;
+	let era = z.div_euclid(146097);
+	let doe = z.rem_euclid(146097);
+	let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let day = doy - (153 * mp + 2) / 5 + 1;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 };
+	let year = yoe + era * 400 + i64::from(month <= 2);
+
+	format!(
+		"{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+		time / 3600, time / 60 % 60, time % 60,
+	)
+}
+//endregion
+
+#[cfg(test)]
+mod tests_pbf {
+	use std::io::Write;
+
+	use flate2::Compression;
+	use flate2::write::ZlibEncoder;
+
+	use super::*;
+
+	/// Minimal protobuf encoder for building test files.
+	#[derive(Default)]
+	struct Encoder(Vec<u8>);
+
+	impl Encoder {
+		fn varint(mut self, mut v: u64) -> Self {
+			while v >= 0x80 {
+				self.0.push(v as u8 | 0x80);
+				v >>= 7;
+			}
+			self.0.push(v as u8);
+			self
+		}
+
+		fn uint(self, field: u64, v: u64) -> Self {
+			self.varint(field << 3).varint(v)
+		}
+
+		fn sint(self, field: u64, v: i64) -> Self {
+			self.uint(field, ((v << 1) ^ (v >> 63)) as u64)
+		}
+
+		fn bytes(self, field: u64, b: &[u8]) -> Self {
+			let mut e = self.varint(field << 3 | 2).varint(b.len() as u64);
+			e.0.extend_from_slice(b);
+			e
+		}
+
+		fn packed(self, field: u64, values: &[u64]) -> Self {
+			let packed = values.iter().fold(Encoder::default(), |e, v| e.varint(*v));
+			self.bytes(field, &packed.0)
+		}
+
+		fn packed_delta(self, field: u64, values: &[i64]) -> Self {
+			let mut prev = 0;
+			let deltas = values.iter()
+				.map(|v| {
+					let d = v - prev;
+					prev = *v;
+					((d << 1) ^ (d >> 63)) as u64
+				})
+				.collect::<Vec<_>>();
+			self.packed(field, &deltas)
+		}
+	}
+
+	fn blob(kind: &str, data: &[u8], file: &mut Vec<u8>) {
+		let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+		zlib.write_all(data).unwrap();
+
+		let blob = Encoder::default()
+			.uint(2, data.len() as u64)
+			.bytes(3, &zlib.finish().unwrap());
+		let header = Encoder::default()
+			.bytes(1, kind.as_bytes())
+			.uint(3, blob.0.len() as u64);
+
+		file.extend_from_slice(&(header.0.len() as u32).to_be_bytes());
+		file.extend_from_slice(&header.0);
+		file.extend_from_slice(&blob.0);
+	}
+
+	fn sample() -> Vec<u8> {
+		let bbox = Encoder::default()
+			.sint(1, -81_902_120_000).sint(2, -81_901_260_000)
+			.sint(3, 41_304_530_000).sint(4, 41_303_650_000);
+		let header = Encoder::default()
+			.bytes(1, &bbox.0)
+			.bytes(4, b"OsmSchema-V0.6")
+			.bytes(4, b"DenseNodes")
+			.bytes(16, b"test-writer");
+
+		let strings = ["", "alice", "highway", "residential", "amenity", "cafe", "outer"]
+			.iter()
+			.fold(Encoder::default(), |e, s| e.bytes(1, s.as_bytes()));
+
+		let dense_info = Encoder::default()
+			.packed(1, &[1, 2])
+			.packed_delta(2, &[1672531200, 1672531200])
+			.packed_delta(3, &[100, 100])
+			.packed_delta(4, &[11, 11])
+			.packed_delta(5, &[1, 1]);
+		let dense = Encoder::default()
+			.packed_delta(1, &[1, 2])
+			.bytes(5, &dense_info.0)
+			.packed_delta(8, &[41303650, 41304530])
+			.packed_delta(9, &[-81901710, -81901690])
+			.packed(10, &[0, 4, 5, 0]);
+
+		let way = Encoder::default()
+			.uint(1, 10)
+			.packed(2, &[2])
+			.packed(3, &[3])
+			.bytes(4, &Encoder::default().uint(1, 1).uint(4, 11).uint(5, 1).0)
+			.packed_delta(8, &[1, 2]);
+
+		let relation = Encoder::default()
+			.uint(1, 20)
+			.packed(2, &[2])
+			.packed(3, &[3])
+			.bytes(4, &Encoder::default().uint(1, 3).uint(4, 11).uint(5, 1).0)
+			.packed(8, &[6, 0])
+			.packed_delta(9, &[10, 2])
+			.packed(10, &[1, 0]);
+
+		let group = Encoder::default().bytes(2, &dense.0).bytes(3, &way.0).bytes(4, &relation.0);
+		// Coordinates above are in units of this granularity, 1e-6 degrees.
+		let block = Encoder::default()
+			.bytes(1, &strings.0)
+			.bytes(2, &group.0)
+			.uint(17, 1000);
+
+		let mut file = Vec::new();
+		blob("OSMHeader", &header.0, &mut file);
+		blob("OSMData", &block.0, &mut file);
+		file
+	}
+
+	#[test]
+	fn parse() {
+		let data = parse_pbf(sample().as_slice()).unwrap();
+
+		assert_eq!(data.generator, "test-writer");
+		assert!(data.bounds.min.approx_eq(&Coordinate::new(41.30365, -81.90212), 1e-5));
+		assert!(data.bounds.max.approx_eq(&Coordinate::new(41.30453, -81.90126), 1e-5));
+
+		assert_eq!(data.nodes.len(), 2);
+		assert!(data.nodes[&2].pos.approx_eq(&Coordinate::new(41.30453, -81.90169), 1e-5));
+		#[cfg(not(feature = "minimal"))]
+		assert_eq!(data.nodes[&2].timestamp, "2023-01-01T00:00:00Z");
+		#[cfg(not(feature = "minimal"))]
+		assert_eq!(data.nodes[&2].user, "alice");
+		#[cfg(not(feature = "minimal"))]
+		assert_eq!(data.nodes[&2].uid, 11);
+		#[cfg(not(feature = "minimal"))]
+		assert_eq!(data.nodes[&2].version, 2);
+		assert_eq!(data.nodes[&1].tags, None);
+		assert_eq!(data.nodes[&2].tags.as_ref().unwrap().get("amenity"), Some("cafe"));
+
+		let way = &data.ways[&10];
+		assert_eq!(way.nodes, vec![1, 2]);
+		#[cfg(not(feature = "minimal"))]
+		assert_eq!(way.user, "alice");
+		#[cfg(not(feature = "minimal"))]
+		assert_eq!(way.uid, 11);
+		assert_eq!(way.tags.as_ref().unwrap().get("highway"), Some("residential"));
+
+		let relation = &data.relations[&20];
+		assert_eq!(relation.members, vec![
+			Member { member_type: MemberType::Way, id: 10, role: "outer".to_string() },
+			Member { member_type: MemberType::Node, id: 2, role: String::new() },
+		]);
+		assert_eq!(relation.version, 3);
+		assert_eq!(relation.user, "alice");
+		assert_eq!(relation.tags.as_ref().unwrap().get("highway"), Some("residential"));
+	}
+
+	#[test]
+	fn unsupported_feature() {
+		let header = Encoder::default().bytes(4, b"HistoricalInformation");
+		let mut file = Vec::new();
+		blob("OSMHeader", &header.0, &mut file);
+
+		assert!(matches!(parse_pbf(file.as_slice()), Err(OsmError::Unsupported(_))));
+		assert!(!parse_pbf(file.as_slice()).unwrap_err().is_syntax());
+	}
+
+	#[test]
+	fn decompression_bomb() {
+		let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+		zlib.write_all(&[0; 1 << 20]).unwrap();
+		let compressed = zlib.finish().unwrap();
+
+		let blob = Encoder::default().uint(2, 1024).bytes(3, &compressed);
+		assert!(matches!(decompress(&blob.0), Err(OsmError::Pbf(_))));
+		let blob = Encoder::default().uint(2, 1 << 20).bytes(3, &compressed);
+		assert_eq!(decompress(&blob.0).unwrap().len(), 1 << 20);
+	}
+
+	#[test]
+	fn overflow() {
+		let block = Block { strings: Vec::new(), granularity: i64::MAX, lat_offset: 0, lon_offset: 0, date_granularity: i64::MAX };
+
+		assert!(matches!(block.coordinate(2, 0), Err(OsmError::Pbf(_))));
+		assert!(matches!(block.timestamp(2), Err(OsmError::Pbf(_))));
+		assert!(block.coordinate(0, 0).is_ok());
+	}
+
+	#[test]
+	fn timestamps() {
+		assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+		assert_eq!(format_timestamp(951782400 + 3661), "2000-02-29T01:01:01Z");
+	}
+}
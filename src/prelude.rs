@@ -0,0 +1,7 @@
+pub use crate::{Bounds, Coordinate, Id, Member, MemberType, NodeMap, Node, Nodes, OsmData, OsmError, Relation, Relations, Tags, Way, Ways};
+pub use crate::{parse, parse_clipped, parse_header, parse_lossy, parse_str, parse_strict, parse_with_progress, parse_with_tag_filter};
+#[cfg(feature = "async")] pub use crate::parse_async;
+#[cfg(feature = "gzip")] pub use crate::parse_auto;
+#[cfg(feature = "memmap")] pub use crate::parse_mmap;
+#[cfg(feature = "pbf")] pub use crate::parse_pbf;
+pub use crate::convert::{Convert, ConvertWith, Projection};
@@ -0,0 +1,100 @@
+use roaring::RoaringTreemap;
+
+use crate::OsmData;
+use crate::parser::Id;
+
+/// A compact set of element ids backed by a roaring bitmap,
+/// much smaller than a [std::collections::HashSet] for large selections of nearby ids.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Selection(RoaringTreemap);
+
+impl Selection {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds an id, returning `true` if it wasn't selected yet.
+	pub fn insert(&mut self, id: Id) -> bool {
+		self.0.insert(id)
+	}
+
+	/// Removes an id, returning `true` if it was selected.
+	pub fn remove(&mut self, id: Id) -> bool {
+		self.0.remove(id)
+	}
+
+	pub fn contains(&self, id: Id) -> bool {
+		self.0.contains(id)
+	}
+
+	pub fn len(&self) -> u64 {
+		self.0.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// Iterates over the ids in ascending order.
+	pub fn iter(&self) -> impl Iterator<Item = Id> + '_ {
+		self.0.iter()
+	}
+
+	/// Ids in either selection.
+	pub fn union(&self, other: &Selection) -> Selection {
+		Self(&self.0 | &other.0)
+	}
+
+	/// Ids in both selections.
+	pub fn intersection(&self, other: &Selection) -> Selection {
+		Self(&self.0 & &other.0)
+	}
+
+	/// Ids in this but not the other selection.
+	pub fn difference(&self, other: &Selection) -> Selection {
+		Self(&self.0 - &other.0)
+	}
+}
+
+impl FromIterator<Id> for Selection {
+	fn from_iter<T: IntoIterator<Item = Id>>(iter: T) -> Self {
+		Self(RoaringTreemap::from_iter(iter))
+	}
+}
+
+impl OsmData {
+	/// Like [OsmData::subset], with the ids given as [Selection]s.
+	pub fn subset_by_selection(&self, nodes: &Selection, ways: &Selection) -> OsmData {
+		self.subset_by(nodes.iter(), ways.iter())
+	}
+}
+
+#[cfg(test)]
+mod tests_selection {
+	use std::collections::HashSet;
+
+	use super::*;
+	use crate::parse_str;
+	use crate::parser::tests_parser::SAMPLE;
+
+	#[test]
+	fn set_operations() {
+		let a = Selection::from_iter([1, 2, 3, u64::MAX]);
+		let b = Selection::from_iter([3, 4]);
+
+		assert!(a.contains(u64::MAX) && !a.contains(4));
+		assert_eq!(a.union(&b).iter().collect::<Vec<_>>(), vec![1, 2, 3, 4, u64::MAX]);
+		assert_eq!(a.intersection(&b).iter().collect::<Vec<_>>(), vec![3]);
+		assert_eq!(a.difference(&b).len(), 3);
+	}
+
+	#[test]
+	fn subset() {
+		let data = parse_str(SAMPLE).unwrap();
+
+		assert_eq!(
+			data.subset_by_selection(&Selection::from_iter([4, 99]), &Selection::from_iter([10])),
+			data.subset(&HashSet::from([4, 99]), &HashSet::from([10])),
+		);
+	}
+}
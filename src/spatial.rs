@@ -0,0 +1,277 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{wide, Coordinate, Float, MemberType, Nodes, OsmData, Tags};
+use crate::convert::R;
+use crate::parser::Id;
+
+//region Grid
+/// Uniform grid bucketing node ids, with cells at least `tolerance_m` wide everywhere in the dataset.
+/// Any two nodes within the tolerance are therefore in the same or in adjacent cells.
+pub(crate) struct Grid {
+	lat_size: f64,
+	lon_size: f64,
+	cells: HashMap<(i64, i64), Vec<Id>>,
+}
+
+impl Grid {
+	pub(crate) fn new(nodes: &Nodes, tolerance_m: Float) -> Self {
+		// Lower bound so a zero tolerance still produces valid (tiny) cells.
+		let lat_size = (wide(tolerance_m) / wide(R)).to_degrees().max(1e-9);
+
+		// Longitude degrees shrink towards the poles, so size the cells for the highest latitude.
+		let max_lat = nodes.values().map(|n| wide(n.pos.lat).abs()).fold(0., f64::max);
+		let lon_size = lat_size / max_lat.to_radians().cos().max(0.01);
+
+		let mut grid = Self { lat_size, lon_size, cells: HashMap::new() };
+		for node in nodes.values() {
+			let cell = grid.cell(&node.pos);
+			grid.cells.entry(cell).or_default().push(node.id);
+		}
+		grid
+	}
+
+	fn cell(&self, c: &Coordinate) -> (i64, i64) {
+		(
+			(wide(c.lat) / self.lat_size).floor() as i64,
+			(wide(c.lon) / self.lon_size).floor() as i64,
+		)
+	}
+
+	/// Ids in the cell of `c` and the 8 surrounding cells, a superset of all nodes within the tolerance.
+	pub(crate) fn candidates(&self, c: &Coordinate) -> impl Iterator<Item = Id> + '_ {
+		let (lat, lon) = self.cell(c);
+		(lat - 1..=lat + 1)
+			.flat_map(move |i| (lon - 1..=lon + 1).map(move |j| (i, j)))
+			.filter_map(|cell| self.cells.get(&cell))
+			.flatten()
+			.copied()
+	}
+}
+//endregion
+
+//region Snap
+impl OsmData {
+	/// Merges nodes that are within `tolerance_m` meters of each other.
+	///
+	/// Nodes are visited in ascending id order, each unmerged node becomes the representative
+	/// of all unmerged nodes within the tolerance of it. Merged nodes are removed, their tags are
+	/// combined into the representative with [Tags::merge_tags], and way refs are rewritten to the
+	/// representative, dropping refs that would repeat the previous one.
+	/// Node members of relations are rewritten too, but kept even if they repeat.
+	/// Returns the number of removed nodes.
+	pub fn snap_nodes(&mut self, tolerance_m: Float) -> usize {
+		let grid = Grid::new(&self.nodes, tolerance_m);
+
+		let mut ids = self.nodes.keys().copied().collect::<Vec<_>>();
+		ids.sort_unstable();
+
+		let mut replaced = HashMap::<Id, Id>::new();
+		for id in ids {
+			if replaced.contains_key(&id) {
+				continue;
+			}
+
+			let pos = self.nodes[&id].pos;
+			let mut cluster = grid.candidates(&pos)
+				.filter(|other| *other != id && !replaced.contains_key(other))
+				.filter(|other| self.nodes[other].pos.distance_to(&pos) <= tolerance_m)
+				.collect::<Vec<_>>();
+			cluster.sort_unstable();
+
+			for other in cluster {
+				let merged = self.nodes.remove(&other).unwrap();
+				if let Some(tags) = merged.tags {
+					self.nodes.get_mut(&id).unwrap().tags
+						.get_or_insert_with(Tags::new)
+						.merge_tags(&tags);
+				}
+				replaced.insert(other, id);
+			}
+		}
+
+		if !replaced.is_empty() {
+			for way in self.ways.values_mut() {
+				for node in way.nodes.iter_mut() {
+					if let Some(id) = replaced.get(node) {
+						*node = *id;
+					}
+				}
+				way.nodes.dedup();
+			}
+
+			let members = self.relations.values_mut().flat_map(|r| r.members.iter_mut());
+			for member in members.filter(|m| m.member_type == MemberType::Node) {
+				if let Some(id) = replaced.get(&member.id) {
+					member.id = *id;
+				}
+			}
+		}
+
+		replaced.len()
+	}
+
+	/// Groups nodes within `tolerance_m` meters of each other, e.g. unmerged duplicates from an import.
+	/// This is the read-only counterpart to [OsmData::snap_nodes] and groups nodes the same way:
+	/// each node not grouped yet, in ascending id order, collects the others within the tolerance of it.
+	/// A tolerance of `0` groups only nodes with exactly equal coordinates.
+	///
+	/// Only groups of at least two nodes are returned, sorted by their first id, ids are sorted within each group.
+	pub fn coincident_nodes(&self, tolerance_m: Float) -> Vec<Vec<Id>> {
+		let grid = Grid::new(&self.nodes, tolerance_m);
+
+		let mut ids = self.nodes.keys().copied().collect::<Vec<_>>();
+		ids.sort_unstable();
+
+		let mut grouped = HashSet::<Id>::new();
+		let mut groups = Vec::new();
+		for id in ids {
+			if grouped.contains(&id) {
+				continue;
+			}
+
+			let pos = &self.nodes[&id].pos;
+			let mut group = grid.candidates(pos)
+				.filter(|other| *other != id && !grouped.contains(other))
+				.filter(|other| {
+					let other = &self.nodes[other].pos;
+					other == pos || other.distance_to(pos) <= tolerance_m
+				})
+				.collect::<Vec<_>>();
+			if group.is_empty() {
+				continue;
+			}
+
+			group.push(id);
+			group.sort_unstable();
+			grouped.extend(&group);
+			groups.push(group);
+		}
+		groups
+	}
+}
+
+#[cfg(test)]
+mod tests_snap {
+	use super::*;
+	use crate::{Member, Node, Relation, Relations, Way, Ways};
+
+	#[test]
+	fn snap() {
+		let node = |id, lat, lon, tags: &[(&str, &str)]| Node {
+			id,
+			tags: (!tags.is_empty()).then(|| crate::structs::tests_tags::tags(tags)),
+			..Node::from_coordinate(Coordinate::new(lat, lon))
+		};
+
+		let mut data = OsmData {
+			nodes: Nodes::from_iter([
+				(1, node(1, 50., 10., &[("name", "A")])),
+				(2, node(2, 50.000001, 10.000001, &[("name", "B"), ("ele", "100")])),
+				(3, node(3, 50.001, 10., &[])),
+				(4, node(4, 50.001, 10.000001, &[])),
+			]),
+			ways: Ways::from_iter([(1, Way { id: 1, nodes: vec![1, 2, 3, 4], ..Default::default() })]),
+			relations: Relations::from_iter([(1, Relation {
+				id: 1,
+				members: vec![Member { member_type: MemberType::Node, id: 4, role: "stop".to_string() }],
+				..Default::default()
+			})]),
+			..Default::default()
+		};
+
+		assert_eq!(data.snap_nodes(0.5), 2);
+		assert_eq!(data.ways[&1].nodes, vec![1, 3]);
+		assert_eq!(data.relations[&1].members[0].id, 3);
+
+		let tags = data.nodes[&1].tags.as_ref().unwrap();
+		assert_eq!(tags.get("name"), Some("A"));
+		assert_eq!(tags.get("ele"), Some("100"));
+	}
+
+	#[test]
+	fn coincident() {
+		let node = |id, lat, lon| (id, Node { id, ..Node::from_coordinate(Coordinate::new(lat, lon)) });
+		let data = OsmData {
+			nodes: Nodes::from_iter([
+				node(1, 50., 10.),
+				node(2, 50.001, 10.),
+				node(3, 50., 10.),
+				node(4, 50.000001, 10.000001),
+				node(5, 50.001, 10.),
+				node(6, 51., 10.),
+			]),
+			..Default::default()
+		};
+
+		assert_eq!(data.coincident_nodes(0.), vec![vec![1, 3], vec![2, 5]]);
+		assert_eq!(data.coincident_nodes(0.5), vec![vec![1, 3, 4], vec![2, 5]]);
+		assert!(OsmData::default().coincident_nodes(1.).is_empty());
+	}
+}
+//endregion
+
+//region Tiles
+impl OsmData {
+	/// Buckets node ids by the slippy map tile containing them at `zoom`, see [Coordinate::to_tile].
+	/// Ids are sorted within each tile.
+	pub fn nodes_by_tile(&self, zoom: u8) -> HashMap<(u32, u32), Vec<Id>> {
+		let mut tiles = HashMap::<(u32, u32), Vec<Id>>::new();
+		for node in self.nodes.values() {
+			tiles.entry(node.pos.to_tile(zoom)).or_default().push(node.id);
+		}
+		for ids in tiles.values_mut() {
+			ids.sort_unstable();
+		}
+		tiles
+	}
+}
+
+#[cfg(test)]
+mod tests_tiles {
+	use crate::{Coordinate, Node, Nodes, OsmData};
+
+	#[test]
+	fn tiles() {
+		let node = |id, lat, lon| (id, Node { id, ..Node::from_coordinate(Coordinate::new(lat, lon)) });
+		let data = OsmData {
+			nodes: Nodes::from_iter([node(1, 52.5, 13.4), node(2, -33.9, 151.2), node(3, 52.6, 13.3)]),
+			..Default::default()
+		};
+
+		let tiles = data.nodes_by_tile(4);
+		assert_eq!(tiles.len(), 2);
+		assert_eq!(tiles[&(8, 5)], vec![1, 3]);
+		assert_eq!(tiles[&(14, 9)], vec![2]);
+	}
+}
+//endregion
+
+//region Radius
+impl OsmData {
+	/// Ids of all nodes within `radius_m` meters of `center` by [Coordinate::distance_to], sorted.
+	pub fn nodes_within(&self, center: &Coordinate, radius_m: Float) -> Vec<Id> {
+		let mut ids = self.nodes.values()
+			.filter(|n| n.pos.distance_to(center) <= radius_m)
+			.map(|n| n.id)
+			.collect::<Vec<_>>();
+		ids.sort_unstable();
+		ids
+	}
+}
+
+#[cfg(test)]
+mod tests_radius {
+	use crate::{parse_str, Coordinate};
+	use crate::parser::tests_parser::SAMPLE;
+
+	#[test]
+	fn within() {
+		let data = parse_str(SAMPLE).unwrap();
+		let center = Coordinate::new(41.30407, -81.90169);
+
+		assert_eq!(data.nodes_within(&center, 1000.), vec![1, 2, 3, 4]);
+		assert_eq!(data.nodes_within(&center, 40.), vec![3, 4]);
+		assert!(data.nodes_within(&Coordinate::new(0., 0.), 1000.).is_empty());
+	}
+}
+//endregion
@@ -0,0 +1,241 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{narrow, wide, Bounds, Float, OsmData};
+use crate::parser::Id;
+
+//region Tags
+impl OsmData {
+	/// Counts how many nodes and ways carry each value of the tag `key`.
+	pub fn tag_value_histogram(&self, key: &str) -> HashMap<String, usize> {
+		let node_tags = self.nodes.values().filter_map(|n| n.tags.as_ref());
+		let way_tags = self.ways.values().filter_map(|w| w.tags.as_ref());
+
+		let mut histogram = HashMap::new();
+		for tags in node_tags.chain(way_tags) {
+			if let Some(value) = tags.get(key) {
+				*histogram.entry(value.to_string()).or_default() += 1;
+			}
+		}
+		histogram
+	}
+
+	/// Sums the lengths of ways in meters, grouped by their `highway` value.
+	/// Ways without a `highway` tag or with missing nodes are skipped.
+	pub fn length_by_highway(&self) -> HashMap<String, Float> {
+		let mut lengths = HashMap::<String, f64>::new();
+		for way in self.ways.values() {
+			let Some(highway) = way.tags.as_ref().and_then(|t| t.get("highway")) else { continue };
+			if let Some(length) = self.way_length(way.id) {
+				*lengths.entry(highway.to_string()).or_default() += wide(length);
+			}
+		}
+
+		lengths.into_iter().map(|(k, v)| (k, narrow(v))).collect()
+	}
+}
+
+#[cfg(test)]
+mod tests_tags {
+	use crate::parse_str;
+	use crate::parser::tests_parser::SAMPLE;
+
+	#[test]
+	fn histogram() {
+		let data = parse_str(SAMPLE).unwrap();
+
+		let highway = data.tag_value_histogram("highway");
+		assert_eq!(highway.len(), 2);
+		assert_eq!(highway["residential"], 1);
+		assert_eq!(highway["footway"], 1);
+
+		let name = data.tag_value_histogram("name");
+		assert_eq!(name["Main Street"], 1);
+		assert_eq!(name["Corner Cafe"], 1);
+
+		assert!(data.tag_value_histogram("surface").is_empty());
+	}
+
+	#[test]
+	fn length_by_highway() {
+		let mut data = parse_str(SAMPLE).unwrap();
+		let mut footway = data.ways[&11].clone();
+		footway.id = 12;
+		footway.nodes = vec![1, 3];
+		data.ways.insert(12, footway);
+		data.ways.get_mut(&10).unwrap().tags = None;
+
+		let lengths = data.length_by_highway();
+		let expected = data.way_length(11).unwrap() + data.way_length(12).unwrap();
+
+		assert_eq!(lengths.len(), 1);
+		assert!((lengths["footway"] - expected).abs() < 0.01);
+	}
+}
+//endregion
+
+//region Contributors
+impl OsmData {
+	/// Users of all nodes and ways, counted once per element they last edited.
+	#[cfg(not(feature = "minimal"))]
+	fn users(&self) -> impl Iterator<Item = &str> {
+		self.nodes.values().map(|n| n.user.as_str())
+			.chain(self.ways.values().map(|w| w.user.as_str()))
+			.filter(|u| !u.is_empty())
+	}
+
+	/// Nodes and ways have no users with the `minimal` feature.
+	#[cfg(feature = "minimal")]
+	fn users(&self) -> impl Iterator<Item = &str> {
+		std::iter::empty()
+	}
+
+	/// Distinct `user` names of all nodes and ways.
+	/// Empty names, as in redacted or anonymized data, are excluded.
+	/// Always empty with the `minimal` feature.
+	pub fn contributors(&self) -> HashSet<String> {
+		self.users().map(str::to_string).collect()
+	}
+
+	/// Counts the nodes and ways last edited by each user, excluding empty names like [OsmData::contributors].
+	pub fn contributor_counts(&self) -> HashMap<String, usize> {
+		let mut counts = HashMap::new();
+		for user in self.users() {
+			*counts.entry(user.to_string()).or_default() += 1;
+		}
+		counts
+	}
+}
+
+#[cfg(test)]
+mod tests_contributors {
+	use crate::parse_str;
+	use crate::parser::tests_parser::SAMPLE;
+
+	#[cfg(not(feature = "minimal"))]
+	#[test]
+	fn contributors() {
+		let mut data = parse_str(SAMPLE).unwrap();
+		data.nodes.get_mut(&4).unwrap().user.clear();
+
+		let contributors = data.contributors();
+		assert_eq!(contributors.len(), 2);
+		assert!(contributors.contains("alice") && contributors.contains("bob"));
+
+		let counts = data.contributor_counts();
+		assert_eq!(counts["alice"], 3);
+		assert_eq!(counts["bob"], 2);
+	}
+
+	#[cfg(feature = "minimal")]
+	#[test]
+	fn contributors() {
+		let data = parse_str(SAMPLE).unwrap();
+		assert!(data.contributors().is_empty() && data.contributor_counts().is_empty());
+	}
+}
+//endregion
+
+//region Validation
+impl OsmData {
+	/// Returns the sorted ids of ways that can't form a valid geometry:
+	/// open ways with fewer than 2 node refs, and closed rings with fewer than 4 (3 distinct nodes plus the closing one).
+	pub fn degenerate_ways(&self) -> Vec<Id> {
+		let mut ids = self.ways.values()
+			.filter(|w| w.nodes.len() < if w.is_closed() { 4 } else { 2 })
+			.map(|w| w.id)
+			.collect::<Vec<_>>();
+		ids.sort_unstable();
+		ids
+	}
+
+	/// Finds consecutive nodes of ways that are more than `max_gap_m` meters apart, likely disjoint parts of one way.
+	/// Maps each way with gaps to the ascending indices of those segments, where segment `i` joins node `i` and `i + 1`.
+	/// Ways that reference missing nodes are skipped.
+	pub fn find_way_gaps(&self, max_gap_m: Float) -> HashMap<Id, Vec<usize>> {
+		self.ways.keys()
+			.filter_map(|id| {
+				let gaps = self.way_coordinates(*id)?
+					.windows(2)
+					.enumerate()
+					.filter(|(_, pair)| pair[0].distance_to(&pair[1]) > max_gap_m)
+					.map(|(i, _)| i)
+					.collect::<Vec<_>>();
+				(!gaps.is_empty()).then_some((*id, gaps))
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests_validation {
+	use super::*;
+	use crate::{Coordinate, Node, Way, Ways};
+
+	#[test]
+	fn degenerate() {
+		let data = OsmData {
+			ways: [vec![], vec![1], vec![1, 2], vec![1, 1], vec![1, 2, 1], vec![1, 2, 3, 1]]
+				.into_iter()
+				.zip(1..)
+				.map(|(nodes, id)| (id, Way { id, nodes, ..Default::default() }))
+				.collect::<Ways>(),
+			..Default::default()
+		};
+
+		assert_eq!(data.degenerate_ways(), vec![1, 2, 4, 5]);
+	}
+
+	#[test]
+	fn way_gaps() {
+		let mut data = crate::parse_str(crate::parser::tests_parser::SAMPLE).unwrap();
+		// way 10 continues about 1 km north of node 2, and way 12 references a missing node
+		data.nodes.insert(5, Node::from_coordinate(Coordinate::new(41.31453, -81.90169)));
+		data.nodes.insert(6, Node::from_coordinate(Coordinate::new(41.31463, -81.90169)));
+		data.ways.get_mut(&10).unwrap().nodes.extend([5, 6]);
+		data.ways.insert(12, Way { id: 12, nodes: vec![1, 99], ..Default::default() });
+
+		assert_eq!(data.find_way_gaps(500.), HashMap::from([(10, vec![1])]));
+		assert_eq!(data.find_way_gaps(50.).get(&10), Some(&vec![0, 1]));
+		assert!(data.find_way_gaps(5000.).is_empty());
+	}
+}
+//endregion
+
+//region Summary
+/// Counts and bounds of an [OsmData], small enough to copy around instead of the data itself.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Summary {
+	pub node_count: usize,
+	pub way_count: usize,
+	pub bounds: Bounds,
+}
+
+impl OsmData {
+	/// Copies the counts and bounds, which stay valid after the data is dropped.
+	pub fn summary(&self) -> Summary {
+		Summary {
+			node_count: self.nodes.len(),
+			way_count: self.ways.len(),
+			bounds: self.bounds,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests_summary {
+	use crate::{parse_str, Coordinate};
+	use crate::parser::tests_parser::SAMPLE;
+
+	#[test]
+	fn summary() {
+		let data = parse_str(SAMPLE).unwrap();
+		let summaries = [data.summary(); 2];
+		drop(data);
+
+		assert_eq!(summaries[0], summaries[1]);
+		assert_eq!(summaries[0].node_count, 4);
+		assert_eq!(summaries[0].way_count, 2);
+		assert!(summaries[0].bounds.contains(&Coordinate::new(41.304, -81.9017)));
+	}
+}
+//endregion
@@ -1,10 +1,20 @@
-use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::ops::{Add, Deref, DerefMut, Div, Mul, Sub};
+use std::str::FromStr;
 
-use crate::Float;
-use crate::parser::{Id, Nodes, Tags, Ways};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::{self, Visitor};
+
+use crate::{narrow, wide, Float};
+use crate::convert::R;
+use crate::parser::{Id, Nodes, Relations, Ways};
+
+/// Unmodeled JSON fields of a document or element, sorted by key.
+#[cfg(feature = "preserve-unknown")]
+pub type Extra = BTreeMap<String, serde_json::Value>;
 
 //region Coordinate
-#[derive(Debug, Default, Clone, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Coordinate {
 	pub lat: Float,
 	pub lon: Float,
@@ -20,11 +30,282 @@ impl Coordinate {
 	pub const fn new(lat: Float, lon: Float) -> Self {
 		Self { lat, lon }
 	}
+
+	/// Returns the `(x, y)` of the slippy map tile containing this [Coordinate] at `zoom`,
+	/// see https://wiki.openstreetmap.org/wiki/Slippy_map_tilenames.
+	/// Latitudes beyond the Web Mercator limit of about ±85.05° are clamped to the edge tiles.
+	pub fn to_tile(&self, zoom: u8) -> (u32, u32) {
+		let n = 2f64.powi(zoom as i32);
+		let lat = wide(self.lat).to_radians();
+
+		let x = (wide(self.lon) + 180.) / 360. * n;
+		let y = (1. - lat.tan().asinh() / std::f64::consts::PI) / 2. * n;
+
+		let clamp = |v: f64| v.floor().clamp(0., n - 1.) as u32;
+		(clamp(x), clamp(y))
+	}
+
+	/// Returns `[lat, lon]`, note that GeoJSON uses the reverse order.
+	pub const fn to_array(&self) -> [Float; 2] {
+		[self.lat, self.lon]
+	}
+
+	/// Returns `(lat, lon)`.
+	pub const fn to_tuple(&self) -> (Float, Float) {
+		(self.lat, self.lon)
+	}
+
+	/// Returns `true` if both components differ by at most `eps`.
+	pub fn approx_eq(&self, other: &Coordinate, eps: Float) -> bool {
+		(self.lat - other.lat).abs() <= eps && (self.lon - other.lon).abs() <= eps
+	}
+
+	/// Great-circle distance in meters to `other`, using the haversine formula.
+	pub fn distance_to(&self, other: &Coordinate) -> Float {
+		self.distance_to_with_radius(other, R)
+	}
+
+	/// Like [Coordinate::distance_to], on a sphere of the given radius in meters.
+	pub fn distance_to_with_radius(&self, other: &Coordinate, radius: Float) -> Float {
+		let (lat1, lat2) = (wide(self.lat).to_radians(), wide(other.lat).to_radians());
+		let d_lat = lat2 - lat1;
+		let d_lon = wide(other.lon - self.lon).to_radians();
+
+		let a = (d_lat / 2.).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.).sin().powi(2);
+
+		narrow(2. * wide(radius) * a.sqrt().asin())
+	}
+
+	/// Initial bearing in degrees (`0..360`, clockwise from north) towards `other`.
+	pub fn bearing_to(&self, other: &Coordinate) -> Float {
+		let (lat1, lat2) = (wide(self.lat).to_radians(), wide(other.lat).to_radians());
+		let d_lon = wide(other.lon - self.lon).to_radians();
+
+		let y = d_lon.sin() * lat2.cos();
+		let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * d_lon.cos();
+
+		narrow((y.atan2(x).to_degrees() + 360.) % 360.)
+	}
+
+	/// The [Coordinate] reached by travelling `distance_m` meters along `bearing_deg` on a sphere.
+	pub fn destination(&self, bearing_deg: Float, distance_m: Float) -> Coordinate {
+		self.destination_with_radius(bearing_deg, distance_m, R)
+	}
+
+	/// Like [Coordinate::destination], on a sphere of the given radius in meters.
+	pub fn destination_with_radius(&self, bearing_deg: Float, distance_m: Float, radius: Float) -> Coordinate {
+		let lat1 = wide(self.lat).to_radians();
+		let lon1 = wide(self.lon).to_radians();
+		let bearing = wide(bearing_deg).to_radians();
+		let delta = wide(distance_m) / wide(radius);
+
+		let lat2 = (lat1.sin() * delta.cos() + lat1.cos() * delta.sin() * bearing.cos()).asin();
+		let lon2 = lon1 + (bearing.sin() * delta.sin() * lat1.cos())
+			.atan2(delta.cos() - lat1.sin() * lat2.sin());
+
+		Coordinate {
+			lat: narrow(lat2.to_degrees()),
+			lon: narrow((lon2.to_degrees() + 540.) % 360. - 180.),
+		}
+	}
+}
+
+impl From<[Float; 2]> for Coordinate {
+	/// Converts from `[lat, lon]`.
+	fn from([lat, lon]: [Float; 2]) -> Self {
+		Self { lat, lon }
+	}
+}
+
+impl From<(Float, Float)> for Coordinate {
+	/// Converts from `(lat, lon)`.
+	fn from((lat, lon): (Float, Float)) -> Self {
+		Self { lat, lon }
+	}
+}
+
+/// Component-wise, like the other operators. They are meant for projected coordinates in planar units,
+/// see [crate::convert::Convert]. On raw latitude and longitude they ignore the curvature of the earth.
+impl Add for Coordinate {
+	type Output = Coordinate;
+
+	fn add(self, rhs: Coordinate) -> Coordinate {
+		Coordinate::new(self.lat + rhs.lat, self.lon + rhs.lon)
+	}
+}
+
+/// Component-wise, see the [Add] impl.
+impl Sub for Coordinate {
+	type Output = Coordinate;
+
+	fn sub(self, rhs: Coordinate) -> Coordinate {
+		Coordinate::new(self.lat - rhs.lat, self.lon - rhs.lon)
+	}
+}
+
+/// Component-wise, see the [Add] impl.
+impl Mul<Float> for Coordinate {
+	type Output = Coordinate;
+
+	fn mul(self, rhs: Float) -> Coordinate {
+		Coordinate::new(self.lat * rhs, self.lon * rhs)
+	}
+}
+
+/// Component-wise, see the [Add] impl.
+impl Div<Float> for Coordinate {
+	type Output = Coordinate;
+
+	fn div(self, rhs: Float) -> Coordinate {
+		Coordinate::new(self.lat / rhs, self.lon / rhs)
+	}
+}
+
+/// [Coordinate] as a GeoJSON-style `[lon, lat]` array instead of the default `{"lat", "lon"}` struct,
+/// for use with `#[serde(with = "osm_parser::lon_lat")]`.
+pub mod lon_lat {
+	use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+	use crate::{Coordinate, Float};
+
+	pub fn serialize<S: Serializer>(c: &Coordinate, serializer: S) -> Result<S::Ok, S::Error> {
+		[c.lon, c.lat].serialize(serializer)
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Coordinate, D::Error> {
+		let [lon, lat] = <[Float; 2]>::deserialize(deserializer)?;
+		Ok(Coordinate { lat, lon })
+	}
+}
+
+/// A [Coordinate] snapped to a fixed grid of 1e-7 degrees (about 1cm), the resolution OSM stores.
+/// Unlike floats, it implements [Eq] and [Hash], so it can be used for deduplication and as a map key.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct QuantizedCoordinate {
+	/// Latitude in units of 1e-7 degrees.
+	pub lat: i32,
+	/// Longitude in units of 1e-7 degrees.
+	pub lon: i32,
+}
+
+impl QuantizedCoordinate {
+	/// Grid cells per degree.
+	pub const SCALE: f64 = 1e7;
+}
+
+impl From<&Coordinate> for QuantizedCoordinate {
+	fn from(value: &Coordinate) -> Self {
+		Self {
+			lat: (wide(value.lat) * Self::SCALE).round() as i32,
+			lon: (wide(value.lon) * Self::SCALE).round() as i32,
+		}
+	}
+}
+
+impl From<Coordinate> for QuantizedCoordinate {
+	fn from(value: Coordinate) -> Self {
+		Self::from(&value)
+	}
+}
+
+impl From<QuantizedCoordinate> for Coordinate {
+	fn from(value: QuantizedCoordinate) -> Self {
+		Coordinate {
+			lat: narrow(value.lat as f64 / QuantizedCoordinate::SCALE),
+			lon: narrow(value.lon as f64 / QuantizedCoordinate::SCALE),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests_coordinate {
+	use super::*;
+
+	#[test]
+	fn destination() {
+		let start = Coordinate::new(41.30409, -81.90169);
+
+		for bearing in [0., 45., 135., 270.] {
+			let end = start.destination(bearing, 1000.);
+			assert!((start.distance_to(&end) - 1000.).abs() < 1.);
+			let d_bearing = (start.bearing_to(&end) - bearing + 540.) % 360. - 180.;
+			assert!(d_bearing.abs() < 0.1);
+		}
+	}
+
+	#[test]
+	fn conversions() {
+		let c = Coordinate::new(41.30365, -81.90171);
+
+		assert_eq!(c.to_array(), [41.30365, -81.90171]);
+		assert_eq!(c.to_tuple(), (41.30365, -81.90171));
+		assert_eq!(Coordinate::from(c.to_array()), c);
+		assert_eq!(Coordinate::from(c.to_tuple()), c);
+	}
+
+	#[test]
+	fn tiles() {
+		assert_eq!(Coordinate::ZERO.to_tile(0), (0, 0));
+		assert_eq!(Coordinate::new(41.30365, -81.90171).to_tile(10), (279, 382));
+		assert_eq!(Coordinate::new(90., 180.).to_tile(2), (3, 0));
+		assert_eq!(Coordinate::new(-90., -180.).to_tile(2), (0, 3));
+	}
+
+	#[test]
+	fn quantized() {
+		let c = Coordinate::new(41.30365, -81.90171);
+		let a = QuantizedCoordinate::from(&c);
+		let b = QuantizedCoordinate::from(Coordinate::new(c.lat + 2e-8, c.lon - 2e-8));
+
+		assert_eq!(a, b);
+		assert_eq!(std::collections::HashSet::from([a, b]).len(), 1);
+		assert!(Coordinate::from(a).approx_eq(&Coordinate::new(41.30365, -81.90171), 1e-5));
+		assert_ne!(a, QuantizedCoordinate::from(Coordinate::new(41.30366, -81.90171)));
+	}
+
+	#[test]
+	fn ops() {
+		let (a, b) = (Coordinate::new(1., 2.), Coordinate::new(3., 6.));
+
+		assert_eq!(a + b, Coordinate::new(4., 8.));
+		assert_eq!(b - a, Coordinate::new(2., 4.));
+		assert_eq!(a * 2., Coordinate::new(2., 4.));
+		assert_eq!((a + b) / 2., Coordinate::new(2., 4.));
+		// interpolation a quarter of the way from a to b
+		assert_eq!(a + (b - a) * 0.25, Coordinate::new(1.5, 3.));
+	}
+
+	#[test]
+	fn lon_lat() {
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		struct Point {
+			#[serde(with = "super::lon_lat")]
+			pos: Coordinate,
+		}
+
+		let point = Point { pos: Coordinate::new(41.5, -81.25) };
+		let json = serde_json::to_string(&point).unwrap();
+
+		assert_eq!(json, r#"{"pos":[-81.25,41.5]}"#);
+		assert_eq!(serde_json::from_str::<Point>(&json).unwrap(), point);
+		assert_eq!(serde_json::to_string(&point.pos).unwrap(), r#"{"lat":41.5,"lon":-81.25}"#);
+	}
+
+	#[test]
+	fn radius() {
+		use crate::convert::{EARTH_RADIUS, EARTH_RADIUS_POLAR};
+
+		let a = Coordinate::new(0., 0.);
+		let b = Coordinate::new(0., 1.);
+
+		assert_eq!(a.distance_to(&b), a.distance_to_with_radius(&b, EARTH_RADIUS));
+		assert!(a.distance_to_with_radius(&b, EARTH_RADIUS_POLAR) < a.distance_to(&b));
+	}
 }
 //endregion
 
 //region Bounds
-#[derive(Debug, Default, Clone, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Bounds {
 	pub min: Coordinate,
 	pub max: Coordinate,
@@ -32,9 +313,13 @@ pub struct Bounds {
 
 #[derive(Default, Deserialize)]
 pub(crate) struct RawBounds {
+	#[serde(alias = "minLat")]
 	pub minlat: Float,
+	#[serde(alias = "maxLat")]
 	pub maxlat: Float,
+	#[serde(alias = "minLon")]
 	pub minlon: Float,
+	#[serde(alias = "maxLon")]
 	pub maxlon: Float,
 }
 
@@ -50,6 +335,8 @@ impl From<RawBounds> for Bounds {
 impl Bounds {
 	pub const ZERO: Self = Self { min: Coordinate::ZERO, max: Coordinate::ZERO };
 	pub const FULL: Self = Self { min: Coordinate::MIN, max: Coordinate::MAX };
+	/// Inverted infinite bounds that contain nothing, the starting point for [Bounds::extend].
+	pub const EMPTY: Self = Self { min: Coordinate::INF, max: Coordinate::NEG_INF };
 
 	pub const fn new(min: Coordinate, max: Coordinate) -> Self {
 		Self { min, max }
@@ -61,17 +348,49 @@ impl Bounds {
 			return Self::ZERO;
 		}
 
-		let mut min = Coordinate::INF;
-		let mut max = Coordinate::NEG_INF;
-
+		let mut bounds = Self::EMPTY;
 		for node in nodes.values() {
-			min.lat = min.lat.min(node.pos.lat);
-			min.lon = min.lon.min(node.pos.lon);
-			max.lat = max.lat.max(node.pos.lat);
-			max.lon = max.lon.max(node.pos.lon);
+			bounds.extend(&node.pos);
 		}
+		bounds
+	}
 
-		Self { min, max }
+	/// Returns `true` if `min` is less than or equal to `max` on both axes and nothing is NaN.
+	/// Degenerate bounds like [Bounds::ZERO] are valid but [empty](Bounds::is_empty),
+	/// inverted bounds like [Bounds::EMPTY] are neither valid nor contain anything.
+	pub fn is_valid(&self) -> bool {
+		self.min.lat <= self.max.lat && self.min.lon <= self.max.lon
+	}
+
+	/// Returns `true` if the [Bounds] have no area, because they are degenerate or not [valid](Bounds::is_valid).
+	pub fn is_empty(&self) -> bool {
+		!(self.min.lat < self.max.lat && self.min.lon < self.max.lon)
+	}
+
+	/// Returns the [Bounds] with `min` and `max` swapped per axis where inverted.
+	pub fn normalized(&self) -> Bounds {
+		Bounds {
+			min: Coordinate::new(self.min.lat.min(self.max.lat), self.min.lon.min(self.max.lon)),
+			max: Coordinate::new(self.min.lat.max(self.max.lat), self.min.lon.max(self.max.lon)),
+		}
+	}
+
+	/// Returns `true` if both corners differ by at most `eps` per component, see [Coordinate::approx_eq].
+	pub fn approx_eq(&self, other: &Bounds, eps: Float) -> bool {
+		self.min.approx_eq(&other.min, eps) && self.max.approx_eq(&other.max, eps)
+	}
+
+	/// Returns `true` if `coord` is inside the [Bounds] or on their edge.
+	pub fn contains(&self, coord: &Coordinate) -> bool {
+		(self.min.lat..=self.max.lat).contains(&coord.lat) && (self.min.lon..=self.max.lon).contains(&coord.lon)
+	}
+
+	/// Grows the [Bounds] to include `coord`.
+	pub fn extend(&mut self, coord: &Coordinate) {
+		self.min.lat = self.min.lat.min(coord.lat);
+		self.min.lon = self.min.lon.min(coord.lon);
+		self.max.lat = self.max.lat.max(coord.lat);
+		self.max.lon = self.max.lon.max(coord.lon);
 	}
 
 	/// Calculates the center [Coordinate] of the current [Bounds].
@@ -81,6 +400,39 @@ impl Bounds {
 			lon: (self.min.lon + self.max.lon) / 2.0,
 		}
 	}
+
+	/// The [Bounds] enclosing all points within `radius_m` meters of `center`, the inverse of [Bounds::center].
+	/// Like [Bounds::expand_meters], clamped to the valid coordinate range near the poles and the antimeridian.
+	pub fn from_center_radius(center: &Coordinate, radius_m: Float) -> Bounds {
+		Bounds { min: *center, max: *center }.expand_meters(radius_m)
+	}
+
+	/// Grows the [Bounds] outward by `margin_m` meters on every side.
+	/// Longitude degrees are scaled by the cosine of the mean latitude.
+	/// Negative margins shrink the box, collapsing it onto its center instead of inverting it.
+	pub fn expand_meters(&self, margin_m: Float) -> Bounds {
+		let center = self.center();
+		let d_lat = (margin_m / R).to_degrees();
+		let d_lon = d_lat / center.lat.to_radians().cos();
+
+		let mut bounds = Bounds {
+			min: Coordinate::new(self.min.lat - d_lat, self.min.lon - d_lon),
+			max: Coordinate::new(self.max.lat + d_lat, self.max.lon + d_lon),
+		};
+
+		if bounds.min.lat > bounds.max.lat {
+			(bounds.min.lat, bounds.max.lat) = (center.lat, center.lat);
+		}
+		if bounds.min.lon > bounds.max.lon {
+			(bounds.min.lon, bounds.max.lon) = (center.lon, center.lon);
+		}
+
+		bounds.min.lat = bounds.min.lat.max(Coordinate::MIN.lat);
+		bounds.min.lon = bounds.min.lon.max(Coordinate::MIN.lon);
+		bounds.max.lat = bounds.max.lat.min(Coordinate::MAX.lat);
+		bounds.max.lon = bounds.max.lon.min(Coordinate::MAX.lon);
+		bounds
+	}
 }
 
 #[cfg(test)]
@@ -94,7 +446,7 @@ mod tests_bounds {
 
 	#[test]
 	fn compute() {
-		let nodes = Nodes::from([
+		let nodes = Nodes::from_iter([
 			(1, Node::from_coordinate(Coordinate::new(41.30365, -81.90171))),
 			(2, Node::from_coordinate(Coordinate::new(41.30453, -81.90169))),
 			(3, Node::from_coordinate(Coordinate::new(41.30407, -81.90212))),
@@ -104,26 +456,330 @@ mod tests_bounds {
 		assert_eq!(Bounds::calculate(&nodes), BOUNDS);
 	}
 
+	#[test]
+	fn extend() {
+		let mut bounds = Bounds::EMPTY;
+		bounds.extend(&Coordinate::new(41.30365, -81.90171));
+		bounds.extend(&Coordinate::new(41.30453, -81.90126));
+		bounds.extend(&Coordinate::new(41.30407, -81.90212));
+
+		assert_eq!(bounds, BOUNDS);
+	}
+
 	#[test]
 	fn center() {
-		#[cfg(feature = "f64")]
-		assert_eq!(BOUNDS.center(), Coordinate::new(41.30409, -81.90169));
-		#[cfg(not(feature = "f64"))]
-		assert_eq!(BOUNDS.center(), Coordinate::new(41.304092, -81.90169));
+		assert!(BOUNDS.center().approx_eq(&Coordinate::new(41.30409, -81.90169), 1e-5));
+	}
+
+	#[test]
+	fn approx_eq() {
+		let shifted = Bounds::new(
+			Coordinate::new(BOUNDS.min.lat + 1e-4, BOUNDS.min.lon),
+			Coordinate::new(BOUNDS.max.lat, BOUNDS.max.lon - 1e-4),
+		);
+
+		assert!(BOUNDS.approx_eq(&shifted, 1e-3));
+		assert!(!BOUNDS.approx_eq(&shifted, 1e-5));
+		assert!(!BOUNDS.approx_eq(&Bounds::ZERO, 1.));
+	}
+
+	#[test]
+	fn validity() {
+		assert!(BOUNDS.is_valid() && !BOUNDS.is_empty());
+		assert!(Bounds::ZERO.is_valid() && Bounds::ZERO.is_empty());
+		assert!(!Bounds::EMPTY.is_valid() && Bounds::EMPTY.is_empty());
+
+		let inverted = Bounds::new(BOUNDS.max, BOUNDS.min);
+		assert!(!inverted.is_valid());
+		assert_eq!(inverted.normalized(), BOUNDS);
+
+		let half = Bounds::new(Coordinate::new(1., 0.), Coordinate::new(0., 1.));
+		assert_eq!(half.normalized(), Bounds::new(Coordinate::new(0., 0.), Coordinate::new(1., 1.)));
+	}
+
+	#[test]
+	fn expand_meters() {
+		let expanded = BOUNDS.expand_meters(100.);
+		let dist = expanded.min.distance_to(&Coordinate::new(BOUNDS.min.lat, expanded.min.lon));
+		assert!((dist - 100.).abs() < 0.5);
+		assert!(expanded.max.lon > BOUNDS.max.lon);
+
+		let shrunk = BOUNDS.expand_meters(-1000.);
+		assert_eq!(shrunk.min, shrunk.max);
+		assert_eq!(Bounds::FULL.expand_meters(1000.), Bounds::FULL);
+	}
+
+	#[test]
+	fn from_center_radius() {
+		let center = Coordinate::new(60., 10.);
+		let bounds = Bounds::from_center_radius(&center, 2000.);
+
+		assert!(bounds.center().approx_eq(&center, 1e-4));
+		assert!((center.distance_to(&Coordinate::new(bounds.max.lat, center.lon)) - 2000.).abs() < 1.);
+		assert!((center.distance_to(&Coordinate::new(center.lat, bounds.max.lon)) - 2000.).abs() < 1.);
+		assert!((bounds.max.lon - bounds.min.lon - 2. * (bounds.max.lat - bounds.min.lat)).abs() < 1e-4);
+
+		assert!(Bounds::from_center_radius(&center, 0.).approx_eq(&Bounds::new(center, center), 1e-6));
+
+		let polar = Bounds::from_center_radius(&Coordinate::new(89.99, 0.), 5000.);
+		assert_eq!(polar.max.lat, Coordinate::MAX.lat);
+	}
+}
+//endregion
+
+//region Tags
+/// Key-value tags of an element.
+/// Dereferences to the underlying [HashMap], so all map methods are available.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Tags(pub HashMap<String, String>);
+
+impl Tags {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the value of `key`.
+	pub fn get(&self, key: &str) -> Option<&str> {
+		self.0.get(key).map(String::as_str)
+	}
+
+	/// Parses the value of `key`, returning [None] if it's missing or invalid.
+	pub fn get_parsed<T: FromStr>(&self, key: &str) -> Option<T> {
+		self.get(key)?.parse().ok()
+	}
+
+	pub fn contains(&self, key: &str) -> bool {
+		self.0.contains_key(key)
+	}
+
+	/// Parses `maxspeed` in km/h, converting values in `mph` and `knots`.
+	/// Returns [None] for missing or unparseable values, including implicit limits like `RU:urban`,
+	/// non-numeric values like `walk` and multiple values like `50;30`.
+	pub fn maxspeed_kmh(&self) -> Option<Float> {
+		let value = self.get("maxspeed")?.trim();
+		let (number, factor) = [("mph", 1.609344), ("knots", 1.852), ("km/h", 1.)]
+			.into_iter()
+			.find_map(|(unit, factor)| value.strip_suffix(unit).map(|n| (n, factor)))
+			.unwrap_or((value, 1.));
+
+		let speed = number.trim().parse::<Float>().ok()?;
+		(speed.is_finite() && speed >= 0.).then_some(speed * factor)
+	}
+
+	/// Parses `lanes`, returning [None] if it's missing or not a single whole number.
+	pub fn lanes(&self) -> Option<u32> {
+		self.get_parsed("lanes")
+	}
+
+	/// Parses `layer`, returning [None] if it's missing or not a single whole number.
+	/// Elements without a `layer` tag are implicitly on layer 0.
+	pub fn layer(&self) -> Option<i32> {
+		self.get_parsed("layer")
+	}
+
+	/// Adds all tags of `other` whose keys aren't present yet, existing values take precedence.
+	pub fn merge_tags(&mut self, other: &Tags) {
+		for (k, v) in other.iter() {
+			if !self.contains(k) {
+				self.0.insert(k.clone(), v.clone());
+			}
+		}
+	}
+}
+
+/// Serializes with keys in sorted order, so output is reproducible.
+impl Serialize for Tags {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		self.0.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+	}
+}
+
+/// Coerces numbers and booleans to strings, since some exporters emit values like `"layer": 1`.
+impl<'de> Deserialize<'de> for Tags {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let tags = HashMap::<String, TagValue>::deserialize(deserializer)?;
+		Ok(Self(tags.into_iter().map(|(k, v)| (k, v.0)).collect()))
+	}
+}
+
+/// A tag value that also accepts JSON scalars, see the [Deserialize] impl of [Tags].
+struct TagValue(String);
+
+impl<'de> Deserialize<'de> for TagValue {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct ScalarVisitor;
+
+		impl Visitor<'_> for ScalarVisitor {
+			type Value = TagValue;
+
+			fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+				f.write_str("a string, number or boolean")
+			}
+
+			fn visit_str<E: de::Error>(self, v: &str) -> Result<TagValue, E> {
+				Ok(TagValue(v.to_string()))
+			}
+
+			fn visit_string<E: de::Error>(self, v: String) -> Result<TagValue, E> {
+				Ok(TagValue(v))
+			}
+
+			fn visit_bool<E: de::Error>(self, v: bool) -> Result<TagValue, E> {
+				Ok(TagValue(v.to_string()))
+			}
+
+			fn visit_i64<E: de::Error>(self, v: i64) -> Result<TagValue, E> {
+				Ok(TagValue(v.to_string()))
+			}
+
+			fn visit_u64<E: de::Error>(self, v: u64) -> Result<TagValue, E> {
+				Ok(TagValue(v.to_string()))
+			}
+
+			fn visit_f64<E: de::Error>(self, v: f64) -> Result<TagValue, E> {
+				Ok(TagValue(v.to_string()))
+			}
+		}
+
+		// Binary formats like bincode can't deserialize self-describing values, but only ever contain strings.
+		if deserializer.is_human_readable() {
+			deserializer.deserialize_any(ScalarVisitor)
+		} else {
+			deserializer.deserialize_string(ScalarVisitor)
+		}
+	}
+}
+
+impl Deref for Tags {
+	type Target = HashMap<String, String>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl DerefMut for Tags {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.0
+	}
+}
+
+impl From<HashMap<String, String>> for Tags {
+	fn from(value: HashMap<String, String>) -> Self {
+		Self(value)
+	}
+}
+
+impl<const N: usize> From<[(String, String); N]> for Tags {
+	fn from(value: [(String, String); N]) -> Self {
+		Self(HashMap::from(value))
+	}
+}
+
+impl FromIterator<(String, String)> for Tags {
+	fn from_iter<T: IntoIterator<Item = (String, String)>>(iter: T) -> Self {
+		Self(HashMap::from_iter(iter))
+	}
+}
+
+#[cfg(test)]
+pub(crate) mod tests_tags {
+	use super::*;
+
+	/// Builds [Tags] from string slices, shared by all tests.
+	pub(crate) fn tags(pairs: &[(&str, &str)]) -> Tags {
+		pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+	}
+
+	#[test]
+	fn accessors() {
+		let tags = tags(&[("lanes", "2"), ("name", "Main Street")]);
+
+		assert_eq!(tags.get("name"), Some("Main Street"));
+		assert_eq!(tags.get_parsed::<u32>("lanes"), Some(2));
+		assert_eq!(tags.get_parsed::<u32>("name"), None);
+		assert!(tags.contains("lanes"));
+		assert_eq!(tags.len(), 2);
+	}
+
+	#[test]
+	fn numeric() {
+		let speed = |v: &str| tags(&[("maxspeed", v)]).maxspeed_kmh();
+
+		assert_eq!(speed("50"), Some(50.));
+		assert_eq!(speed("50 km/h"), Some(50.));
+		assert!((speed("30 mph").unwrap() - 48.28).abs() < 0.01);
+		assert!((speed("30mph").unwrap() - 48.28).abs() < 0.01);
+		assert_eq!(speed("RU:urban"), None);
+		assert_eq!(speed("walk"), None);
+		assert_eq!(speed("50;30"), None);
+		assert_eq!(speed("NaN"), None);
+
+		assert_eq!(tags(&[("lanes", "2")]).lanes(), Some(2));
+		assert_eq!(tags(&[("lanes", "2;3")]).lanes(), None);
+		assert_eq!(tags(&[("layer", "-1")]).layer(), Some(-1));
+		assert_eq!(Tags::new().layer(), None);
+	}
+
+	#[test]
+	fn merge() {
+		let mut a = tags(&[("name", "A")]);
+		a.merge_tags(&tags(&[("name", "B"), ("surface", "asphalt")]));
+
+		assert_eq!(a, tags(&[("name", "A"), ("surface", "asphalt")]));
+	}
+
+	#[test]
+	fn serialize_sorted() {
+		let tags = tags(&[("surface", "asphalt"), ("highway", "primary"), ("name", "A"), ("lanes", "2")]);
+
+		assert_eq!(
+			serde_json::to_string(&tags).unwrap(),
+			r#"{"highway":"primary","lanes":"2","name":"A","surface":"asphalt"}"#,
+		);
+	}
+
+	#[test]
+	fn coerce_scalars() {
+		let parsed = serde_json::from_str::<Tags>(r#"{"layer": 1, "oneway": true, "ele": 12.5, "level": -1, "name": "A"}"#).unwrap();
+		assert_eq!(parsed, tags(&[("layer", "1"), ("oneway", "true"), ("ele", "12.5"), ("level", "-1"), ("name", "A")]));
+
+		assert!(serde_json::from_str::<Tags>(r#"{"name": null}"#).is_err());
+		assert!(serde_json::from_str::<Tags>(r#"{"name": ["A"]}"#).is_err());
 	}
 }
 //endregion
 
 //region Node
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Node {
 	pub id: Id,
 	pub pos: Coordinate,
+	/// See [Relation::timestamp].
+	#[cfg(not(feature = "minimal"))]
 	pub timestamp: String,
+	#[cfg(not(feature = "minimal"))]
 	pub version: u32,
+	#[cfg(not(feature = "minimal"))]
 	pub changeset: u64,
+	/// Display name of the last editor, empty on redacted data.
+	#[cfg(not(feature = "minimal"))]
 	pub user: String,
+	/// Id of the last editor, 0 on redacted data.
+	#[cfg(not(feature = "minimal"))]
+	pub uid: u64,
+	/// `false` for deleted elements in history or change data.
+	pub visible: bool,
 	pub tags: Option<Tags>,
+	/// Fields that aren't modeled, kept for [OsmData::to_json]. Not included in other serializations.
+	#[cfg(feature = "preserve-unknown")]
+	#[serde(skip)]
+	pub extra: Extra,
+}
+
+/// Elements are visible unless stated otherwise.
+const fn visible() -> bool {
+	true
 }
 
 #[derive(Deserialize)]
@@ -131,11 +787,27 @@ pub(crate) struct RawNode {
 	pub id: Id,
 	pub lat: Float,
 	pub lon: Float,
+	#[cfg(not(feature = "minimal"))]
+	#[serde(default)]
 	pub timestamp: String,
+	#[cfg(not(feature = "minimal"))]
+	#[serde(default)]
 	pub version: u32,
+	#[cfg(not(feature = "minimal"))]
+	#[serde(default, alias = "changesetId")]
 	pub changeset: u64,
+	#[cfg(not(feature = "minimal"))]
+	#[serde(default)]
 	pub user: String,
+	#[cfg(not(feature = "minimal"))]
+	#[serde(default)]
+	pub uid: u64,
+	#[serde(default = "visible")]
+	pub visible: bool,
 	pub tags: Option<Tags>,
+	#[cfg(feature = "preserve-unknown")]
+	#[serde(skip)]
+	pub extra: Extra,
 }
 
 impl Node {
@@ -143,11 +815,20 @@ impl Node {
 		Self {
 			id: 0,
 			pos: Coordinate::ZERO,
+			#[cfg(not(feature = "minimal"))]
 			timestamp: String::new(),
+			#[cfg(not(feature = "minimal"))]
 			version: 0,
+			#[cfg(not(feature = "minimal"))]
 			changeset: 0,
+			#[cfg(not(feature = "minimal"))]
 			user: String::new(),
+			#[cfg(not(feature = "minimal"))]
+			uid: 0,
+			visible: true,
 			tags: None,
+			#[cfg(feature = "preserve-unknown")]
+			extra: Extra::new(),
 		}
 	}
 
@@ -156,6 +837,31 @@ impl Node {
 		node.pos = coords;
 		node
 	}
+
+	/// Sets the id.
+	pub const fn with_id(mut self, id: Id) -> Self {
+		self.id = id;
+		self
+	}
+
+	/// Sets the position.
+	pub const fn with_coordinate(mut self, coords: Coordinate) -> Self {
+		self.pos = coords;
+		self
+	}
+
+	/// Adds a tag, replacing an existing value of `key`.
+	pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+		self.tags.get_or_insert_with(Tags::new).insert(key.into(), value.into());
+		self
+	}
+
+	/// Sets the timestamp, e.g. `2023-01-01T00:00:00Z`.
+	#[cfg(not(feature = "minimal"))]
+	pub fn with_timestamp(mut self, timestamp: impl Into<String>) -> Self {
+		self.timestamp = timestamp.into();
+		self
+	}
 }
 
 impl Default for Node {
@@ -169,29 +875,153 @@ impl From<RawNode> for Node {
 		Self {
 			id: value.id,
 			pos: Coordinate::new(value.lat, value.lon),
+			#[cfg(not(feature = "minimal"))]
 			timestamp: value.timestamp,
+			#[cfg(not(feature = "minimal"))]
 			version: value.version,
+			#[cfg(not(feature = "minimal"))]
 			changeset: value.changeset,
+			#[cfg(not(feature = "minimal"))]
 			user: value.user,
+			#[cfg(not(feature = "minimal"))]
+			uid: value.uid,
+			visible: value.visible,
 			tags: value.tags,
+			#[cfg(feature = "preserve-unknown")]
+			extra: value.extra,
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests_node {
+	use super::*;
+
+	#[test]
+	fn builder() {
+		let node = Node::default()
+			.with_id(3)
+			.with_coordinate(Coordinate::new(1., 2.))
+			.with_tag("amenity", "bench")
+			.with_tag("amenity", "cafe")
+			.with_tag("name", "Corner");
+
+		assert_eq!(node.id, 3);
+		assert_eq!(node.pos, Coordinate::new(1., 2.));
+		assert_eq!(node.tags.as_ref().unwrap().get("amenity"), Some("cafe"));
+		assert_eq!(node.tags.as_ref().unwrap().len(), 2);
+		assert_eq!(Node::default().with_id(3), Node { id: 3, ..Default::default() });
+	}
+
+	#[cfg(not(feature = "minimal"))]
+	#[test]
+	fn timestamp() {
+		assert_eq!(Node::default().with_timestamp("2023-01-01T00:00:00Z").timestamp, "2023-01-01T00:00:00Z");
+	}
+}
 //endregion
 
 //region Way
-#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Way {
 	pub id: Id,
+	/// See [Relation::timestamp].
+	#[cfg(not(feature = "minimal"))]
+	#[serde(default)]
 	pub timestamp: String,
+	#[cfg(not(feature = "minimal"))]
+	#[serde(default)]
 	pub version: u32,
+	#[cfg(not(feature = "minimal"))]
+	#[serde(default, alias = "changesetId")]
 	pub changeset: u64,
+	/// Display name of the last editor, empty on redacted data.
+	#[cfg(not(feature = "minimal"))]
+	#[serde(default)]
 	pub user: String,
+	/// Id of the last editor, 0 on redacted data.
+	#[cfg(not(feature = "minimal"))]
+	#[serde(default)]
+	pub uid: u64,
+	/// `false` for deleted elements in history or change data.
+	#[serde(default = "visible")]
+	pub visible: bool,
 	pub nodes: Vec<Id>,
 	pub tags: Option<Tags>,
+	/// Fields that aren't modeled, kept for [OsmData::to_json]. Not included in other serializations.
+	#[cfg(feature = "preserve-unknown")]
+	#[serde(skip)]
+	pub extra: Extra,
+}
+
+impl Default for Way {
+	fn default() -> Self {
+		Self {
+			id: 0,
+			#[cfg(not(feature = "minimal"))]
+			timestamp: String::new(),
+			#[cfg(not(feature = "minimal"))]
+			version: 0,
+			#[cfg(not(feature = "minimal"))]
+			changeset: 0,
+			#[cfg(not(feature = "minimal"))]
+			user: String::new(),
+			#[cfg(not(feature = "minimal"))]
+			uid: 0,
+			visible: true,
+			nodes: Vec::new(),
+			tags: None,
+			#[cfg(feature = "preserve-unknown")]
+			extra: Extra::new(),
+		}
+	}
 }
 
 impl Way {
+	/// Returns `true` if the first and last node refs are identical.
+	pub fn is_closed(&self) -> bool {
+		self.nodes.len() > 1 && self.nodes.first() == self.nodes.last()
+	}
+
+	pub fn node_count(&self) -> usize {
+		self.nodes.len()
+	}
+
+	pub fn first_node(&self) -> Option<Id> {
+		self.nodes.first().copied()
+	}
+
+	pub fn last_node(&self) -> Option<Id> {
+		self.nodes.last().copied()
+	}
+
+	/// Returns `true` if the [Way] describes an area rather than a closed line, decided in this order:
+	///
+	/// 1. Ways that aren't [closed](Way::is_closed) are never areas.
+	/// 2. `area=yes` makes any closed way an area, `area=no` none.
+	/// 3. Ways with a `highway` tag or `junction=roundabout` are lines, e.g. roundabouts or closed footway loops.
+	/// 4. With `tags_hint`, only ways with an area-implying tag are areas: `building` other than `no`, `landuse` or `leisure`.
+	/// 5. Without `tags_hint`, all remaining closed ways are areas.
+	pub fn is_area(&self, tags_hint: bool) -> bool {
+		if !self.is_closed() {
+			return false;
+		}
+
+		let empty = Tags::new();
+		let tags = self.tags.as_ref().unwrap_or(&empty);
+		match tags.get("area") {
+			Some("yes") => return true,
+			Some("no") => return false,
+			_ => {}
+		}
+
+		if tags.contains("highway") || tags.get("junction") == Some("roundabout") {
+			return false;
+		}
+
+		!tags_hint || crate::tags::is_building(tags) || tags.contains("landuse") || tags.contains("leisure")
+	}
+
 	#[deprecated]
 	pub fn tags_to_string(&self) -> String {
 		if let Some(tags) = &self.tags {
@@ -202,10 +1032,139 @@ impl Way {
 		} else { String::new() }
 	}
 }
+
+#[cfg(test)]
+mod tests_way {
+	use super::*;
+
+	fn way(nodes: Vec<Id>, pairs: &[(&str, &str)]) -> Way {
+		Way { nodes, tags: Some(tests_tags::tags(pairs)), ..Default::default() }
+	}
+
+	#[test]
+	fn area() {
+		let ring = vec![1, 2, 3, 1];
+
+		assert!(way(ring.clone(), &[("building", "yes")]).is_area(true));
+		assert!(way(ring.clone(), &[("highway", "pedestrian"), ("area", "yes")]).is_area(true));
+		assert!(!way(ring.clone(), &[("highway", "primary"), ("junction", "roundabout")]).is_area(false));
+		assert!(!way(ring.clone(), &[("leisure", "park"), ("area", "no")]).is_area(true));
+		assert!(!way(ring.clone(), &[("building", "no")]).is_area(true));
+		assert!(!way(ring.clone(), &[("barrier", "fence")]).is_area(true));
+		assert!(way(ring, &[("barrier", "fence")]).is_area(false));
+		assert!(!way(vec![1, 2, 3], &[("landuse", "forest")]).is_area(true));
+	}
+}
+//endregion
+
+//region Relation
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemberType {
+	Node,
+	Way,
+	Relation,
+}
+
+impl MemberType {
+	/// The lowercase name used in OSM documents.
+	pub const fn as_str(&self) -> &'static str {
+		match self {
+			MemberType::Node => "node",
+			MemberType::Way => "way",
+			MemberType::Relation => "relation",
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Member {
+	#[serde(rename = "type")]
+	pub member_type: MemberType,
+	#[serde(rename = "ref")]
+	pub id: Id,
+	pub role: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Relation {
+	pub id: Id,
+	/// Empty, like the version and changeset, for elements that were never uploaded, e.g. new ones in JOSM files.
+	#[serde(default)]
+	pub timestamp: String,
+	#[serde(default)]
+	pub version: u32,
+	#[serde(default, alias = "changesetId")]
+	pub changeset: u64,
+	/// Display name of the last editor, empty on redacted data.
+	#[serde(default)]
+	pub user: String,
+	/// Id of the last editor, 0 on redacted data.
+	#[serde(default)]
+	pub uid: u64,
+	/// `false` for deleted elements in history or change data.
+	#[serde(default = "visible")]
+	pub visible: bool,
+	pub members: Vec<Member>,
+	pub tags: Option<Tags>,
+	/// Fields that aren't modeled, kept for [OsmData::to_json]. Not included in other serializations.
+	#[cfg(feature = "preserve-unknown")]
+	#[serde(skip)]
+	pub extra: Extra,
+}
+
+impl Default for Relation {
+	fn default() -> Self {
+		Self {
+			id: 0,
+			timestamp: String::new(),
+			version: 0,
+			changeset: 0,
+			user: String::new(),
+			uid: 0,
+			visible: true,
+			members: Vec::new(),
+			tags: None,
+			#[cfg(feature = "preserve-unknown")]
+			extra: Extra::new(),
+		}
+	}
+}
+//endregion
+
+//region Element
+/// The type of an element, the same as the type of a relation [Member].
+pub type ElementType = MemberType;
+
+/// A borrowed element of any type, see [OsmData::elements].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Element<'a> {
+	Node(&'a Node),
+	Way(&'a Way),
+	Relation(&'a Relation),
+}
+
+impl Element<'_> {
+	pub fn id(&self) -> Id {
+		match self {
+			Element::Node(n) => n.id,
+			Element::Way(w) => w.id,
+			Element::Relation(r) => r.id,
+		}
+	}
+
+	pub fn tags(&self) -> Option<&Tags> {
+		match self {
+			Element::Node(n) => n.tags.as_ref(),
+			Element::Way(w) => w.tags.as_ref(),
+			Element::Relation(r) => r.tags.as_ref(),
+		}
+	}
+}
 //endregion
 
 //region Osm
-#[derive(Debug, Default, Clone, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OsmData {
 	pub version: String,
 	pub generator: String,
@@ -215,23 +1174,311 @@ pub struct OsmData {
 	pub bounds: Bounds,
 	pub nodes: Nodes,
 	pub ways: Ways,
+	pub relations: Relations,
+	/// Fields that aren't modeled, kept for [OsmData::to_json]. Not included in other serializations.
+	#[cfg(feature = "preserve-unknown")]
+	#[serde(skip)]
+	pub extra: Extra,
 }
 
 #[derive(Deserialize)]
 pub(crate) struct RawOsmData {
 	pub version: String,
 	pub generator: String,
+	/// The legal fields are missing in files saved by editors like JOSM.
+	#[serde(default)]
 	pub copyright: String,
+	#[serde(default)]
 	pub attribution: String,
+	#[serde(default)]
 	pub license: String,
-	pub bounds: RawBounds,
-	pub elements: Vec<serde_json::Value>,
+	/// Missing in single-element responses like `/way/{id}/full`, the bounds are calculated from the nodes then.
+	#[serde(default)]
+	pub bounds: Option<RawBounds>,
+	/// Missing or `null` for some empty responses.
+	#[serde(default)]
+	pub elements: Option<Vec<serde_json::Value>>,
+	/// Flattening makes serde buffer the whole document before deserializing the other fields,
+	/// which roughly doubles peak memory while parsing.
+	#[cfg(feature = "preserve-unknown")]
+	#[serde(flatten)]
+	pub extra: Extra,
+}
+
+/// [RawOsmData] without the elements for [parse_header](crate::parse_header), which serde skips as unknown.
+/// There is no flattened `extra`, so the document is never buffered.
+#[derive(Deserialize)]
+pub(crate) struct RawHeader {
+	pub version: String,
+	pub generator: String,
+	#[serde(default)]
+	pub copyright: String,
+	#[serde(default)]
+	pub attribution: String,
+	#[serde(default)]
+	pub license: String,
+	#[serde(default)]
+	pub bounds: Option<RawBounds>,
 }
 
 impl OsmData {
+	/// Returns `true` if there are no nodes, ways or relations.
+	pub fn is_empty(&self) -> bool {
+		self.nodes.is_empty() && self.ways.is_empty() && self.relations.is_empty()
+	}
+
 	/// Calculates the exact [Bounds] by iterating trough all given [Nodes].
 	pub fn calculate_bounds(&mut self) {
 		self.bounds = Bounds::calculate(&self.nodes);
 	}
+
+	/// Iterates over all nodes, then all ways, then all relations.
+	/// The order within each element type is unspecified.
+	pub fn elements(&self) -> impl Iterator<Item = Element<'_>> {
+		self.nodes.values().map(Element::Node)
+			.chain(self.ways.values().map(Element::Way))
+			.chain(self.relations.values().map(Element::Relation))
+	}
+
+	/// Returns the [Node] with the given id, or [None] if it doesn't exist.
+	pub fn get_node(&self, id: Id) -> Option<&Node> {
+		self.nodes.get(&id)
+	}
+
+	/// Mutable variant of [OsmData::get_node].
+	pub fn get_node_mut(&mut self, id: Id) -> Option<&mut Node> {
+		self.nodes.get_mut(&id)
+	}
+
+	/// Returns the [Way] with the given id, or [None] if it doesn't exist.
+	pub fn get_way(&self, id: Id) -> Option<&Way> {
+		self.ways.get(&id)
+	}
+
+	/// Mutable variant of [OsmData::get_way].
+	pub fn get_way_mut(&mut self, id: Id) -> Option<&mut Way> {
+		self.ways.get_mut(&id)
+	}
+
+	/// Returns the `(type, id, role)` of every member of a [Relation], or [None] if it doesn't exist.
+	pub fn relation_members(&self, rel_id: Id) -> Option<Vec<(MemberType, Id, &str)>> {
+		let relation = self.relations.get(&rel_id)?;
+		Some(relation.members.iter()
+			.map(|m| (m.member_type, m.id, m.role.as_str()))
+			.collect())
+	}
+
+	/// Returns the ids of all way members with role `outer` of a `multipolygon` or `boundary` relation.
+	/// Returns [None] if the relation doesn't exist or is of another type.
+	pub fn relation_outer_ways(&self, rel_id: Id) -> Option<Vec<Id>> {
+		let relation = self.relations.get(&rel_id)?;
+		let kind = relation.tags.as_ref()?.get("type")?;
+		if kind != "multipolygon" && kind != "boundary" {
+			return None;
+		}
+
+		Some(relation.members.iter()
+			.filter(|m| m.member_type == MemberType::Way && m.role == "outer")
+			.map(|m| m.id)
+			.collect())
+	}
+
+	/// Collects the ids of all nodes and ways a [Relation] references, as `(nodes, ways)`,
+	/// expanding member relations up to `max_depth` levels deep. With `0`, only direct members are collected.
+	///
+	/// Each relation is expanded at most once, at its shallowest depth, so cyclic and self-referencing
+	/// relations terminate. Missing member relations are skipped.
+	/// Returns [None] if the relation doesn't exist.
+	pub fn resolve_relation(&self, rel_id: Id, max_depth: usize) -> Option<(HashSet<Id>, HashSet<Id>)> {
+		self.relations.get(&rel_id)?;
+
+		let (mut nodes, mut ways) = (HashSet::new(), HashSet::new());
+		let mut visited = HashSet::from([rel_id]);
+		let mut queue = VecDeque::from([(rel_id, 0)]);
+
+		while let Some((id, depth)) = queue.pop_front() {
+			let Some(relation) = self.relations.get(&id) else { continue };
+			for member in &relation.members {
+				match member.member_type {
+					MemberType::Node => { nodes.insert(member.id); }
+					MemberType::Way => { ways.insert(member.id); }
+					MemberType::Relation => {
+						if depth < max_depth && visited.insert(member.id) {
+							queue.push_back((member.id, depth + 1));
+						}
+					}
+				}
+			}
+		}
+
+		Some((nodes, ways))
+	}
+
+	/// Returns the type of the element with the given id, or [None] if there is none.
+	///
+	/// Ids are only unique per type, so a node and a way can both have the id `5`.
+	/// The first match is returned, checking nodes, then ways, then relations.
+	pub fn element_type(&self, id: Id) -> Option<ElementType> {
+		if self.nodes.contains_key(&id) {
+			Some(ElementType::Node)
+		} else if self.ways.contains_key(&id) {
+			Some(ElementType::Way)
+		} else if self.relations.contains_key(&id) {
+			Some(ElementType::Relation)
+		} else {
+			None
+		}
+	}
+
+	/// Returns the [Coordinate] of a [Node], or [None] if it doesn't exist.
+	pub fn coordinate(&self, node_id: Id) -> Option<Coordinate> {
+		self.nodes.get(&node_id).map(|n| n.pos)
+	}
+
+	/// Resolves the node refs of a [Way] to their [Coordinate]s.
+	/// Returns [None] if the way doesn't exist or references a missing node.
+	pub fn way_coordinates(&self, way_id: Id) -> Option<Vec<Coordinate>> {
+		let way = self.ways.get(&way_id)?;
+		way.nodes.iter()
+			.map(|id| self.coordinate(*id))
+			.collect()
+	}
+
+	/// Consecutive `(from, to)` node ref pairs of a [Way], the edges of a routing graph.
+	/// Returns [None] if the way doesn't exist.
+	pub fn way_edges(&self, way_id: Id) -> Option<Vec<(Id, Id)>> {
+		let way = self.ways.get(&way_id)?;
+		Some(way.nodes.windows(2).map(|pair| (pair[0], pair[1])).collect())
+	}
+
+	/// Like [OsmData::way_edges], with the nodes resolved to their [Coordinate]s.
+	/// Returns [None] if the way doesn't exist or references a missing node.
+	pub fn way_edge_coordinates(&self, way_id: Id) -> Option<Vec<(Coordinate, Coordinate)>> {
+		let coordinates = self.way_coordinates(way_id)?;
+		Some(coordinates.windows(2).map(|pair| (pair[0], pair[1])).collect())
+	}
+
+	/// Calculates the [Bounds] of a [Way] from its coordinates, e.g. for indexing ways spatially.
+	/// Returns [None] if the way doesn't exist, has no nodes or references a missing node.
+	pub fn way_bounds(&self, way_id: Id) -> Option<Bounds> {
+		let coordinates = self.way_coordinates(way_id)?;
+		if coordinates.is_empty() {
+			return None;
+		}
+
+		let mut bounds = Bounds::EMPTY;
+		for c in &coordinates {
+			bounds.extend(c);
+		}
+		Some(bounds)
+	}
+}
+
+#[cfg(test)]
+mod tests_osm {
+	use super::*;
+
+	fn member(member_type: MemberType, id: Id, role: &str) -> Member {
+		Member { member_type, id, role: role.to_string() }
+	}
+
+	#[test]
+	fn relation_members() {
+		let relation = Relation {
+			id: 1,
+			members: vec![
+				member(MemberType::Way, 10, "outer"),
+				member(MemberType::Way, 11, "inner"),
+				member(MemberType::Node, 5, "label"),
+				member(MemberType::Way, 12, "outer"),
+			],
+			tags: Some(Tags::from([("type".to_string(), "multipolygon".to_string())])),
+			..Default::default()
+		};
+		let data = OsmData { relations: Relations::from_iter([(1, relation)]), ..Default::default() };
+
+		assert_eq!(data.relation_members(1).unwrap()[2], (MemberType::Node, 5, "label"));
+		assert_eq!(data.relation_outer_ways(1), Some(vec![10, 12]));
+		assert_eq!(data.relation_members(2), None);
+		assert_eq!(data.relation_outer_ways(2), None);
+	}
+
+	#[test]
+	fn resolve_relation() {
+		let relation = |id, members| (id, Relation { id, members, ..Default::default() });
+		let data = OsmData {
+			relations: Relations::from_iter([
+				relation(1, vec![member(MemberType::Way, 10, ""), member(MemberType::Relation, 2, ""), member(MemberType::Relation, 1, "")]),
+				relation(2, vec![member(MemberType::Node, 5, ""), member(MemberType::Relation, 3, ""), member(MemberType::Relation, 9, "")]),
+				relation(3, vec![member(MemberType::Way, 11, ""), member(MemberType::Relation, 1, "")]),
+			]),
+			..Default::default()
+		};
+
+		assert_eq!(data.resolve_relation(1, 0), Some((HashSet::new(), HashSet::from([10]))));
+		assert_eq!(data.resolve_relation(1, 1), Some((HashSet::from([5]), HashSet::from([10]))));
+		assert_eq!(data.resolve_relation(1, 100), Some((HashSet::from([5]), HashSet::from([10, 11]))));
+		assert_eq!(data.resolve_relation(3, 1), Some((HashSet::new(), HashSet::from([10, 11]))));
+		assert_eq!(data.resolve_relation(4, 1), None);
+	}
+
+	#[test]
+	fn getters() {
+		let mut data = crate::parse_str(crate::parser::tests_parser::SAMPLE).unwrap();
+
+		#[cfg(not(feature = "minimal"))]
+		assert_eq!(data.get_node(2).map(|n| n.version), Some(2));
+		assert_eq!(data.get_way(11).map(|w| w.nodes.len()), Some(2));
+		assert!(data.get_node(10).is_none() && data.get_way(1).is_none());
+
+		assert_eq!(data.element_type(2), Some(ElementType::Node));
+		assert_eq!(data.element_type(11), Some(ElementType::Way));
+		assert_eq!(data.element_type(20), Some(ElementType::Relation));
+		assert_eq!(data.element_type(5), None);
+		data.ways.insert(1, Way { id: 1, ..Default::default() });
+		assert_eq!(data.element_type(1), Some(ElementType::Node));
+
+		data.get_way_mut(10).unwrap().nodes.push(3);
+		data.get_node_mut(3).unwrap().pos = Coordinate::ZERO;
+		assert_eq!(data.way_coordinates(10).unwrap()[2], Coordinate::ZERO);
+	}
+
+	#[test]
+	fn way_edges() {
+		let mut data = crate::parse_str(crate::parser::tests_parser::SAMPLE).unwrap();
+		data.ways.get_mut(&10).unwrap().nodes.push(3);
+
+		assert_eq!(data.way_edges(10), Some(vec![(1, 2), (2, 3)]));
+		assert_eq!(data.way_edge_coordinates(10).unwrap()[1], (data.nodes[&2].pos, data.nodes[&3].pos));
+		assert_eq!(data.way_edges(12), None);
+
+		data.ways.get_mut(&11).unwrap().nodes.truncate(1);
+		assert_eq!(data.way_edges(11), Some(vec![]));
+		assert_eq!(data.way_edge_coordinates(11), Some(vec![]));
+	}
+
+	#[test]
+	fn way_bounds() {
+		let mut data = crate::parse_str(crate::parser::tests_parser::SAMPLE).unwrap();
+
+		let bounds = data.way_bounds(11).unwrap();
+		assert_eq!(bounds, Bounds::new(data.nodes[&3].pos, data.nodes[&4].pos));
+		assert_eq!(data.way_bounds(12), None);
+
+		data.ways.get_mut(&11).unwrap().nodes.push(99);
+		assert_eq!(data.way_bounds(11), None);
+		data.ways.get_mut(&11).unwrap().nodes.clear();
+		assert_eq!(data.way_bounds(11), None);
+	}
+
+	#[test]
+	fn elements() {
+		let data = crate::parse_str(crate::parser::tests_parser::SAMPLE).unwrap();
+		let elements = data.elements().collect::<Vec<_>>();
+
+		assert_eq!(elements.len(), 7);
+		assert!(matches!(elements[..4], [Element::Node(_), Element::Node(_), Element::Node(_), Element::Node(_)]));
+		assert!(matches!(elements[4..], [Element::Way(_), Element::Way(_), Element::Relation(_)]));
+	}
 }
 //endregion
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+use crate::{OsmData, Tags};
+
+//region Classification
+/// Common values of the `highway` key, see https://wiki.openstreetmap.org/wiki/Key:highway.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum HighwayKind {
+	Motorway,
+	Trunk,
+	Primary,
+	Secondary,
+	Tertiary,
+	Unclassified,
+	Residential,
+	MotorwayLink,
+	TrunkLink,
+	PrimaryLink,
+	SecondaryLink,
+	TertiaryLink,
+	LivingStreet,
+	Service,
+	Pedestrian,
+	Track,
+	Footway,
+	Cycleway,
+	Bridleway,
+	Steps,
+	Path,
+	Other(String),
+}
+
+impl From<&str> for HighwayKind {
+	fn from(value: &str) -> Self {
+		match value {
+			"motorway" => HighwayKind::Motorway,
+			"trunk" => HighwayKind::Trunk,
+			"primary" => HighwayKind::Primary,
+			"secondary" => HighwayKind::Secondary,
+			"tertiary" => HighwayKind::Tertiary,
+			"unclassified" => HighwayKind::Unclassified,
+			"residential" => HighwayKind::Residential,
+			"motorway_link" => HighwayKind::MotorwayLink,
+			"trunk_link" => HighwayKind::TrunkLink,
+			"primary_link" => HighwayKind::PrimaryLink,
+			"secondary_link" => HighwayKind::SecondaryLink,
+			"tertiary_link" => HighwayKind::TertiaryLink,
+			"living_street" => HighwayKind::LivingStreet,
+			"service" => HighwayKind::Service,
+			"pedestrian" => HighwayKind::Pedestrian,
+			"track" => HighwayKind::Track,
+			"footway" => HighwayKind::Footway,
+			"cycleway" => HighwayKind::Cycleway,
+			"bridleway" => HighwayKind::Bridleway,
+			"steps" => HighwayKind::Steps,
+			"path" => HighwayKind::Path,
+			other => HighwayKind::Other(other.to_string()),
+		}
+	}
+}
+
+/// Returns the [HighwayKind] if the element has a `highway` tag.
+pub fn is_highway(tags: &Tags) -> Option<HighwayKind> {
+	tags.get("highway").map(HighwayKind::from)
+}
+
+/// Returns `true` if the element has a `building` tag other than `building=no`.
+pub fn is_building(tags: &Tags) -> bool {
+	tags.get("building").is_some_and(|v| v != "no")
+}
+
+/// Returns the `name` tag, falling back to `name:en`.
+pub fn name(tags: &Tags) -> Option<&str> {
+	tags.get("name").or_else(|| tags.get("name:en"))
+}
+
+#[cfg(test)]
+mod tests_tags {
+	use super::*;
+	use crate::structs::tests_tags::tags;
+
+	#[test]
+	fn highway() {
+		assert_eq!(is_highway(&tags(&[("highway", "residential")])), Some(HighwayKind::Residential));
+		assert_eq!(is_highway(&tags(&[("highway", "raceway")])), Some(HighwayKind::Other("raceway".to_string())));
+		assert_eq!(is_highway(&tags(&[("building", "yes")])), None);
+	}
+
+	#[test]
+	fn building() {
+		assert!(is_building(&tags(&[("building", "house")])));
+		assert!(!is_building(&tags(&[("building", "no")])));
+		assert!(!is_building(&Tags::new()));
+	}
+
+	#[test]
+	fn names() {
+		assert_eq!(name(&tags(&[("name", "Köln"), ("name:en", "Cologne")])), Some("Köln"));
+		assert_eq!(name(&tags(&[("name:en", "Cologne")])), Some("Cologne"));
+		assert_eq!(name(&Tags::new()), None);
+	}
+}
+//endregion
+
+//region Normalization
+impl OsmData {
+	/// Mutable tags of all nodes, ways and relations that have any.
+	pub(crate) fn tags_mut(&mut self) -> impl Iterator<Item = &mut Tags> {
+		self.nodes.values_mut().filter_map(|n| n.tags.as_mut())
+			.chain(self.ways.values_mut().filter_map(|w| w.tags.as_mut()))
+			.chain(self.relations.values_mut().filter_map(|r| r.tags.as_mut()))
+	}
+
+	/// Renames tag keys on all elements according to `mapping` (old key to new key).
+	/// If the new key already exists, its value is kept like in [Tags::merge_tags].
+	pub fn rename_tag_keys(&mut self, mapping: &HashMap<String, String>) {
+		let mut mapping = mapping.iter().collect::<Vec<_>>();
+		mapping.sort_unstable();
+
+		for tags in self.tags_mut() {
+			let renamed = mapping.iter()
+				.filter_map(|(old, new)| tags.remove(*old).map(|v| ((*new).clone(), v)))
+				.collect::<Vec<_>>();
+
+			for (k, v) in renamed {
+				tags.entry(k).or_insert(v);
+			}
+		}
+	}
+
+	/// Replaces values of the tag `key` on all elements according to `mapping` (old value to new value).
+	pub fn remap_tag_values(&mut self, key: &str, mapping: &HashMap<String, String>) {
+		for tags in self.tags_mut() {
+			if let Some(value) = tags.get_mut(key) {
+				if let Some(new) = mapping.get(value) {
+					value.clone_from(new);
+				}
+			}
+		}
+	}
+
+	/// Copies the tags `keys` of each way onto its member nodes.
+	///
+	/// Tags the node already has are never overwritten, like with [Tags::merge_tags].
+	/// Nodes shared by ways with different values get the value of the way with the lowest id.
+	pub fn propagate_way_tags(&mut self, keys: &[&str]) {
+		let mut ways = self.ways.values().collect::<Vec<_>>();
+		ways.sort_unstable_by_key(|w| w.id);
+
+		for way in ways {
+			let Some(way_tags) = &way.tags else { continue };
+			let propagated = keys.iter()
+				.filter_map(|k| way_tags.get_key_value(*k))
+				.map(|(k, v)| (k.clone(), v.clone()))
+				.collect::<Tags>();
+			if propagated.is_empty() {
+				continue;
+			}
+
+			for id in &way.nodes {
+				if let Some(node) = self.nodes.get_mut(id) {
+					node.tags.get_or_insert_with(Tags::new).merge_tags(&propagated);
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests_normalization {
+	use super::*;
+	use crate::{Node, Nodes, Way, Ways};
+	use crate::structs::tests_tags::tags;
+
+	fn mapping(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+		tags(pairs).0
+	}
+
+	#[test]
+	fn normalize() {
+		let mut data = OsmData {
+			nodes: Nodes::from_iter([(1, Node { tags: Some(tags(&[("postal_code", "1"), ("addr:postcode", "2")])), ..Default::default() })]),
+			ways: Ways::from_iter([(1, Way { tags: Some(tags(&[("oneway", "true"), ("postal_code", "3")])), ..Default::default() })]),
+			..Default::default()
+		};
+
+		data.rename_tag_keys(&mapping(&[("postal_code", "addr:postcode")]));
+		data.remap_tag_values("oneway", &mapping(&[("true", "yes"), ("false", "no")]));
+
+		assert_eq!(data.nodes[&1].tags, Some(tags(&[("addr:postcode", "2")])));
+		assert_eq!(data.ways[&1].tags, Some(tags(&[("oneway", "yes"), ("addr:postcode", "3")])));
+	}
+
+	#[test]
+	fn propagate() {
+		let node = |id, t: &[(&str, &str)]| (id, Node { id, tags: (!t.is_empty()).then(|| tags(t)), ..Default::default() });
+		let way = |id, nodes: Vec<u64>, t: &[(&str, &str)]| (id, Way { id, nodes, tags: Some(tags(t)), ..Default::default() });
+		let mut data = OsmData {
+			nodes: Nodes::from_iter([node(1, &[]), node(2, &[("surface", "gravel")]), node(3, &[])]),
+			ways: Ways::from_iter([
+				way(2, vec![2, 3], &[("surface", "paved"), ("name", "B")]),
+				way(1, vec![1, 3], &[("surface", "asphalt"), ("highway", "path")]),
+			]),
+			..Default::default()
+		};
+
+		data.propagate_way_tags(&["surface", "lit"]);
+
+		assert_eq!(data.nodes[&1].tags, Some(tags(&[("surface", "asphalt")])));
+		assert_eq!(data.nodes[&2].tags, Some(tags(&[("surface", "gravel")])));
+		assert_eq!(data.nodes[&3].tags, Some(tags(&[("surface", "asphalt")])));
+	}
+}
+//endregion
@@ -0,0 +1,418 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{Member, MemberType, OsmData, Way, Ways};
+use crate::parser::Id;
+
+//region Components
+/// Minimal union-find over dense indices.
+struct UnionFind {
+	parent: Vec<usize>,
+}
+
+impl UnionFind {
+	fn new(len: usize) -> Self {
+		Self { parent: (0..len).collect() }
+	}
+
+	fn find(&mut self, mut i: usize) -> usize {
+		while self.parent[i] != i {
+			self.parent[i] = self.parent[self.parent[i]];
+			i = self.parent[i];
+		}
+		i
+	}
+
+	fn union(&mut self, a: usize, b: usize) {
+		let (a, b) = (self.find(a), self.find(b));
+		if a != b {
+			self.parent[b] = a;
+		}
+	}
+}
+
+impl OsmData {
+	/// Groups way ids into connected clusters.
+	/// Two ways are connected if they share **any** node, not just their endpoints.
+	/// Ids are sorted within each cluster, and clusters are sorted by their smallest id.
+	pub fn connected_components(&self) -> Vec<Vec<Id>> {
+		let mut way_ids = self.ways.keys().copied().collect::<Vec<_>>();
+		way_ids.sort_unstable();
+
+		let mut uf = UnionFind::new(way_ids.len());
+		let mut first_way_of_node = HashMap::<Id, usize>::new();
+
+		for (i, id) in way_ids.iter().enumerate() {
+			for node in &self.ways[id].nodes {
+				match first_way_of_node.get(node) {
+					Some(&other) => uf.union(other, i),
+					None => { first_way_of_node.insert(*node, i); }
+				}
+			}
+		}
+
+		let mut clusters = HashMap::<usize, Vec<Id>>::new();
+		for (i, id) in way_ids.iter().enumerate() {
+			clusters.entry(uf.find(i)).or_default().push(*id);
+		}
+
+		let mut components = clusters.into_values().collect::<Vec<_>>();
+		components.sort_unstable_by_key(|c| c[0]);
+		components
+	}
+}
+
+#[cfg(test)]
+mod tests_components {
+	use super::*;
+
+	fn way(id: Id, nodes: Vec<Id>) -> (Id, Way) {
+		(id, Way { id, nodes, ..Default::default() })
+	}
+
+	#[test]
+	fn components() {
+		let data = OsmData {
+			ways: Ways::from_iter([
+				way(1, vec![1, 2, 3]),
+				way(2, vec![4, 2, 5]),
+				way(3, vec![5, 6]),
+				way(4, vec![7, 8]),
+			]),
+			..Default::default()
+		};
+
+		assert_eq!(data.connected_components(), vec![vec![1, 2, 3], vec![4]]);
+	}
+}
+//endregion
+
+//region Split
+impl OsmData {
+	/// Splits every way into segments at nodes that are referenced more than once,
+	/// either by several ways or repeatedly by the same way.
+	///
+	/// Segments are assigned new sequential way ids starting at 1, in order of the original way ids.
+	/// Tags and metadata are copied onto every segment, nodes are kept unchanged.
+	/// Ways with fewer than 2 nodes have no segments and are dropped.
+	///
+	/// Way members of relations are replaced by the segments of their way, in order and with the same role.
+	/// Members whose way is missing or was dropped are removed, since their ids would clash with the new ones.
+	pub fn split_ways_at_intersections(&self) -> OsmData {
+		let mut refs = HashMap::<Id, usize>::new();
+		for way in self.ways.values() {
+			for node in &way.nodes {
+				*refs.entry(*node).or_default() += 1;
+			}
+		}
+
+		let mut way_ids = self.ways.keys().copied().collect::<Vec<_>>();
+		way_ids.sort_unstable();
+
+		let mut ways = Ways::default();
+		let mut segments = HashMap::<Id, Vec<Id>>::new();
+		let mut next_id = 1;
+
+		for id in way_ids {
+			let way = &self.ways[&id];
+			let mut start = 0;
+
+			for i in 1..way.nodes.len() {
+				if i == way.nodes.len() - 1 || refs[&way.nodes[i]] > 1 {
+					let segment = Way {
+						id: next_id,
+						nodes: way.nodes[start..=i].to_vec(),
+						..way.clone()
+					};
+					ways.insert(next_id, segment);
+					segments.entry(id).or_default().push(next_id);
+					next_id += 1;
+					start = i;
+				}
+			}
+		}
+
+		let mut relations = self.relations.clone();
+		for relation in relations.values_mut() {
+			relation.members = relation.members.iter()
+				.flat_map(|m| match m.member_type {
+					MemberType::Way => segments.get(&m.id).into_iter().flatten()
+						.map(|&id| Member { id, ..m.clone() })
+						.collect(),
+					_ => vec![m.clone()],
+				})
+				.collect();
+		}
+
+		OsmData {
+			ways,
+			relations,
+			..self.clone()
+		}
+	}
+
+	/// Ids of nodes referenced by more than one way, where routing edges split.
+	/// Unlike [OsmData::split_ways_at_intersections], nodes repeated only within one way don't count.
+	pub fn junction_nodes(&self) -> HashSet<Id> {
+		let mut first_way = HashMap::<Id, Id>::new();
+		let mut junctions = HashSet::new();
+		for way in self.ways.values() {
+			for node in &way.nodes {
+				if *first_way.entry(*node).or_insert(way.id) != way.id {
+					junctions.insert(*node);
+				}
+			}
+		}
+		junctions
+	}
+}
+
+#[cfg(test)]
+mod tests_split {
+	use super::*;
+	use crate::{Relation, Relations, Tags};
+
+	#[test]
+	fn split() {
+		let tags = Tags::from([("highway".to_string(), "residential".to_string())]);
+		let data = OsmData {
+			ways: Ways::from_iter([
+				(10, Way { id: 10, nodes: vec![1, 2, 3], tags: Some(tags.clone()), ..Default::default() }),
+				(20, Way { id: 20, nodes: vec![4, 2, 5, 6, 4], ..Default::default() }),
+				(30, Way { id: 30, nodes: vec![7], ..Default::default() }),
+			]),
+			..Default::default()
+		};
+
+		let split = data.split_ways_at_intersections();
+		let mut segments = split.ways.values()
+			.map(|w| (w.id, w.nodes.clone()))
+			.collect::<Vec<_>>();
+		segments.sort_unstable();
+
+		assert_eq!(segments, vec![
+			(1, vec![1, 2]),
+			(2, vec![2, 3]),
+			(3, vec![4, 2]),
+			(4, vec![2, 5, 6, 4]),
+		]);
+		assert_eq!(split.ways[&2].tags, Some(tags));
+	}
+
+	#[test]
+	fn split_relation_members() {
+		let member = |member_type, id, role: &str| Member { member_type, id, role: role.to_string() };
+		let data = OsmData {
+			ways: Ways::from_iter([
+				(10, Way { id: 10, nodes: vec![1, 2, 3], ..Default::default() }),
+				(20, Way { id: 20, nodes: vec![4, 2], ..Default::default() }),
+			]),
+			relations: Relations::from_iter([(7, Relation {
+				id: 7,
+				members: vec![
+					member(MemberType::Node, 1, "stop"),
+					member(MemberType::Way, 10, "forward"),
+					member(MemberType::Way, 99, ""),
+					member(MemberType::Way, 20, ""),
+				],
+				..Default::default()
+			})]),
+			..Default::default()
+		};
+
+		let split = data.split_ways_at_intersections();
+		assert_eq!(split.relations[&7].members, vec![
+			member(MemberType::Node, 1, "stop"),
+			member(MemberType::Way, 1, "forward"),
+			member(MemberType::Way, 2, "forward"),
+			member(MemberType::Way, 3, ""),
+		]);
+	}
+
+	#[test]
+	fn junctions() {
+		let data = OsmData {
+			ways: Ways::from_iter([
+				(1, Way { id: 1, nodes: vec![1, 2, 3], ..Default::default() }),
+				(2, Way { id: 2, nodes: vec![4, 2, 5], ..Default::default() }),
+				(3, Way { id: 3, nodes: vec![6, 7, 8, 6], ..Default::default() }),
+			]),
+			..Default::default()
+		};
+
+		assert_eq!(data.junction_nodes(), HashSet::from([2]));
+	}
+}
+//endregion
+
+//region Adjacency
+impl OsmData {
+	/// Maps every way id to the sorted ids of other ways sharing an endpoint with it.
+	/// Only the first and last node are considered, nodes shared in the middle of a way are ignored,
+	/// see [OsmData::connected_components] for that.
+	pub fn way_adjacency(&self) -> HashMap<Id, Vec<Id>> {
+		let mut ways_at_endpoint = HashMap::<Id, Vec<Id>>::new();
+		for way in self.ways.values() {
+			let endpoints = way.first_node().into_iter().chain(way.last_node());
+			for node in endpoints {
+				ways_at_endpoint.entry(node).or_default().push(way.id);
+			}
+		}
+
+		self.ways.values()
+			.map(|way| {
+				let mut adjacent = way.first_node().into_iter()
+					.chain(way.last_node())
+					.flat_map(|node| &ways_at_endpoint[&node])
+					.copied()
+					.filter(|id| *id != way.id)
+					.collect::<Vec<_>>();
+				adjacent.sort_unstable();
+				adjacent.dedup();
+				(way.id, adjacent)
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests_adjacency {
+	use super::*;
+
+	#[test]
+	fn adjacency() {
+		let data = OsmData {
+			ways: Ways::from_iter([
+				(1, Way { id: 1, nodes: vec![1, 2, 3], ..Default::default() }),
+				(2, Way { id: 2, nodes: vec![3, 4], ..Default::default() }),
+				(3, Way { id: 3, nodes: vec![4, 5, 1], ..Default::default() }),
+				(4, Way { id: 4, nodes: vec![6, 2, 7], ..Default::default() }),
+			]),
+			..Default::default()
+		};
+
+		let adjacency = data.way_adjacency();
+		assert_eq!(adjacency[&1], vec![2, 3]);
+		assert_eq!(adjacency[&2], vec![1, 3]);
+		assert_eq!(adjacency[&3], vec![1, 2]);
+		// shares node 2 only in the middle
+		assert!(adjacency[&4].is_empty());
+	}
+}
+//endregion
+
+//region Compact
+/// Mapping between original and compact ids, returned by [OsmData::compact_ids].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IdRemap {
+	/// Original node ids, indexed by the new id.
+	pub nodes: Vec<Id>,
+	/// Original way ids, indexed by the new id.
+	pub ways: Vec<Id>,
+}
+
+impl IdRemap {
+	/// Returns [None] if no node had the new id `id`.
+	pub fn original_node(&self, id: Id) -> Option<Id> {
+		self.nodes.get(usize::try_from(id).ok()?).copied()
+	}
+
+	/// Returns [None] if no way had the new id `id`.
+	pub fn original_way(&self, id: Id) -> Option<Id> {
+		self.ways.get(usize::try_from(id).ok()?).copied()
+	}
+
+	/// Returns [None] if there was no node with the original id `id`.
+	pub fn new_node(&self, id: Id) -> Option<Id> {
+		self.nodes.binary_search(&id).ok().map(|i| i as Id)
+	}
+
+	/// Returns [None] if there was no way with the original id `id`.
+	pub fn new_way(&self, id: Id) -> Option<Id> {
+		self.ways.binary_search(&id).ok().map(|i| i as Id)
+	}
+}
+
+impl OsmData {
+	/// Renumbers nodes and ways to the dense ranges `0..n` in the order of their original ids,
+	/// rewriting way refs and relation members. Relation ids are kept.
+	///
+	/// Ids that are referenced but missing are renumbered as well, so refs stay unambiguous.
+	/// Returns the [IdRemap] to translate back to the original ids.
+	pub fn compact_ids(&mut self) -> IdRemap {
+		let members = |t| self.relations.values()
+			.flat_map(|r| &r.members)
+			.filter(move |m| m.member_type == t)
+			.map(|m| m.id);
+
+		let mut nodes = self.nodes.keys().copied()
+			.chain(self.ways.values().flat_map(|w| w.nodes.iter().copied()))
+			.chain(members(MemberType::Node))
+			.collect::<Vec<_>>();
+		nodes.sort_unstable();
+		nodes.dedup();
+
+		let mut ways = self.ways.keys().copied()
+			.chain(members(MemberType::Way))
+			.collect::<Vec<_>>();
+		ways.sort_unstable();
+		ways.dedup();
+
+		let remap = IdRemap { nodes, ways };
+		let new_node = |id| remap.new_node(id).unwrap();
+		let new_way = |id| remap.new_way(id).unwrap();
+
+		self.nodes = std::mem::take(&mut self.nodes).into_values()
+			.map(|mut node| {
+				node.id = new_node(node.id);
+				(node.id, node)
+			})
+			.collect();
+		self.ways = std::mem::take(&mut self.ways).into_values()
+			.map(|mut way| {
+				way.id = new_way(way.id);
+				for node in way.nodes.iter_mut() {
+					*node = new_node(*node);
+				}
+				(way.id, way)
+			})
+			.collect();
+		for member in self.relations.values_mut().flat_map(|r| r.members.iter_mut()) {
+			match member.member_type {
+				MemberType::Node => member.id = new_node(member.id),
+				MemberType::Way => member.id = new_way(member.id),
+				MemberType::Relation => {}
+			}
+		}
+
+		remap
+	}
+}
+
+#[cfg(test)]
+mod tests_compact {
+	use crate::parse_str;
+	use crate::parser::tests_parser::SAMPLE;
+
+	#[test]
+	fn compact() {
+		let mut data = parse_str(SAMPLE).unwrap();
+		let original = data.clone();
+		let remap = data.compact_ids();
+
+		assert_eq!(remap.nodes, vec![1, 2, 3, 4]);
+		assert_eq!(remap.ways, vec![10, 11]);
+		assert_eq!(data.ways[&0].nodes, vec![0, 1]);
+		assert_eq!(data.ways[&1].nodes, vec![2, 3]);
+		assert_eq!(data.relations[&20].members[0].id, 0);
+
+		for (id, node) in &data.nodes {
+			assert_eq!(node.id, *id);
+			assert_eq!(node.pos, original.nodes[&remap.original_node(*id).unwrap()].pos);
+		}
+		assert_eq!(remap.original_way(1), Some(11));
+		assert_eq!(remap.new_way(11), Some(1));
+		assert_eq!(remap.new_node(5), None);
+		assert_eq!(remap.original_node(4), None);
+	}
+}
+//endregion